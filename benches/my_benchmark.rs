@@ -1,3 +1,7 @@
+// `bench_function_over_inputs` is deprecated in newer criterion releases, and newer clippy
+// releases lint against some of the casts and arguments below
+#![allow(deprecated, clippy::unnecessary_cast, clippy::unit_arg)]
+
 #[macro_use]
 extern crate lazy_static;
 extern crate rand;
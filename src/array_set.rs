@@ -0,0 +1,1454 @@
+//! A fixed-capacity ordered set based on a 2-level rotated array, which never allocates.
+//!
+//! The set stores its data and auxiliary arrays inline, with room for a number of values
+//! fixed at compile time, and shares the search and update machinery of [`RotatedArraySet`].
+//! Since the subarray of each index is fixed by its position alone, a set of capacity `N`
+//! never has more than `subarray_count(N)`, i.e. `O(√N)`, subarrays.
+//!
+//! [`RotatedArraySet`]: ../struct.RotatedArraySet.html
+
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{FromIterator, FusedIterator};
+use core::mem::{self, MaybeUninit};
+use core::ops::{BitAnd, BitOr, BitXor, Deref, DerefMut, RangeBounds, Sub};
+use core::ptr;
+use core::slice;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use crate::{
+    get_subarray_idx_from_array_idx, insert_raw, remove_raw, sort_subarrays, Buffer, Chunks,
+    Difference, Intersection, Iter, Layout, Range, SymmetricDifference, Union,
+};
+
+// A vector with inline storage for up to `N` elements, which panics rather than grow.
+struct InlineVec<T, const N: usize> {
+    buf: [MaybeUninit<T>; N],
+    // the first `len` elements of `buf` are initialized
+    len: usize,
+}
+
+impl<T, const N: usize> InlineVec<T, N> {
+    const UNINIT: MaybeUninit<T> = MaybeUninit::uninit();
+
+    const fn new() -> Self {
+        InlineVec {
+            buf: [Self::UNINIT; N],
+            len: 0,
+        }
+    }
+
+    fn clear(&mut self) {
+        let len = self.len;
+        // if dropping an element panics, the rest are leaked rather than dropped twice
+        self.len = 0;
+        // SAFETY: the first `len` elements were initialized, and are no longer reachable.
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                self.buf.as_mut_ptr() as *mut T,
+                len,
+            ));
+        }
+    }
+}
+
+impl<T, const N: usize> Deref for InlineVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        // SAFETY: the first `len` elements are initialized.
+        unsafe { slice::from_raw_parts(self.buf.as_ptr() as *const T, self.len) }
+    }
+}
+
+impl<T, const N: usize> DerefMut for InlineVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        // SAFETY: the first `len` elements are initialized.
+        unsafe { slice::from_raw_parts_mut(self.buf.as_mut_ptr() as *mut T, self.len) }
+    }
+}
+
+impl<T, const N: usize> Buffer<T> for InlineVec<T, N> {
+    fn insert(&mut self, index: usize, value: T) {
+        assert!(index <= self.len, "insertion index out of bounds");
+        self.push(value);
+        self[index..].rotate_right(1);
+    }
+
+    fn remove(&mut self, index: usize) -> T {
+        assert!(index < self.len, "removal index out of bounds");
+        self[index..].rotate_left(1);
+        self.pop().unwrap()
+    }
+
+    fn push(&mut self, value: T) {
+        assert!(self.len < N, "capacity exceeded");
+        self.buf[self.len] = MaybeUninit::new(value);
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // SAFETY: the element was initialized, and is no longer reachable.
+        Some(unsafe { self.buf[self.len].as_ptr().read() })
+    }
+}
+
+impl<T, const N: usize> Drop for InlineVec<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for InlineVec<T, N> {
+    fn clone(&self) -> Self {
+        let mut clone = InlineVec::new();
+        for value in self.iter() {
+            clone.push(value.clone());
+        }
+        clone
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for InlineVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// An ordered set based on a 2-level rotated array, with a fixed capacity of `N` values,
+/// which never allocates.
+///
+/// The set supports the same constant-time rank and select, and `O(√n)` inserts
+/// and deletes, as [`RotatedArraySet`], but stores its values inline, so it is suitable for
+/// targets without a heap, or code that can't afford to allocate. Inserting a new value into
+/// a full set fails (see [`try_insert`]).
+///
+/// Stable Rust can't size an array by a function of `N`, so the number `S` of subarray offsets
+/// the set has room for is a separate parameter, which must be at least [`subarray_count`]`(N)`,
+/// or about `√(2N)`. Instantiating a set with a smaller `S` fails to compile.
+///
+/// # Examples
+///
+/// ```
+/// use rotated_array_set::{subarray_count, ArrayRotatedSet};
+///
+/// // Room for 16 values, without touching the heap.
+/// let mut readings: ArrayRotatedSet<u16, 16, { subarray_count(16) }> = ArrayRotatedSet::new();
+///
+/// for &reading in &[310, 297, 305, 412, 288] {
+///     readings.insert(reading);
+/// }
+///
+/// // Find the median reading in constant time.
+/// assert_eq!(readings.select(readings.len() / 2), Some(&305));
+///
+/// // A full set hands back values it can't hold.
+/// while !readings.is_full() {
+///     readings.insert(readings.last().unwrap() + 1);
+/// }
+/// assert!(readings.try_insert(1).is_err());
+/// ```
+///
+/// [`RotatedArraySet`]: struct.RotatedArraySet.html
+/// [`subarray_count`]: fn.subarray_count.html
+/// [`try_insert`]: struct.ArrayRotatedSet.html#method.try_insert
+#[derive(Debug, Clone)]
+pub struct ArrayRotatedSet<T, const N: usize, const S: usize> {
+    data: InlineVec<T, N>,
+    min_indexes: InlineVec<usize, S>,
+}
+
+/// Returns the number of subarrays that `len` values are divided into by a rotated array,
+/// which is the least number `S` of subarrays an `ArrayRotatedSet<T, N, S>` of capacity
+/// `N = len` needs room for.
+///
+/// # Examples
+///
+/// ```
+/// use rotated_array_set::{subarray_count, ArrayRotatedSet};
+///
+/// // subarrays of 1, 2, 3 and 4 values hold 10 values
+/// assert_eq!(subarray_count(10), 4);
+/// assert_eq!(subarray_count(11), 5);
+///
+/// let set: ArrayRotatedSet<u32, 1000, { subarray_count(1000) }> = ArrayRotatedSet::new();
+/// ```
+///
+/// Too few subarrays are rejected at compile time:
+///
+/// ```compile_fail
+/// use rotated_array_set::ArrayRotatedSet;
+///
+/// let set: ArrayRotatedSet<u32, 11, 4> = ArrayRotatedSet::new();
+/// ```
+pub const fn subarray_count(len: usize) -> usize {
+    if len == 0 {
+        0
+    } else {
+        get_subarray_idx_from_array_idx(len - 1) + 1
+    }
+}
+
+/// The error returned by [`try_insert`] when an `ArrayRotatedSet` is full,
+/// which holds the value that could not be inserted.
+///
+/// [`try_insert`]: struct.ArrayRotatedSet.html#method.try_insert
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError<T>(pub T);
+
+impl<T> fmt::Display for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "insufficient capacity")
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: fmt::Debug> Error for CapacityError<T> {}
+
+impl<T, const N: usize, const S: usize> ArrayRotatedSet<T, N, S>
+where
+    T: Ord,
+{
+    // Evaluated whenever `new` is instantiated, so that too small an `S` fails to compile.
+    const ENOUGH_SUBARRAYS: () = assert!(
+        S >= subarray_count(N),
+        "ArrayRotatedSet needs room for at least subarray_count(N) subarrays"
+    );
+
+    /// Makes a new, empty `ArrayRotatedSet`.
+    ///
+    /// This is a constant-time operation, which can be used to initialize a `static`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut set: ArrayRotatedSet<i32, 8, 4> = ArrayRotatedSet::new();
+    /// ```
+    pub const fn new() -> Self {
+        let () = Self::ENOUGH_SUBARRAYS;
+        ArrayRotatedSet {
+            data: InlineVec::new(),
+            min_indexes: InlineVec::new(),
+        }
+    }
+
+    /// Returns the number of values the set can hold.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let set: ArrayRotatedSet<i32, 8, 4> = ArrayRotatedSet::new();
+    /// assert_eq!(set.capacity(), 8);
+    /// ```
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Clears the set, removing all values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut set: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// set.insert(1);
+    /// set.clear();
+    /// assert!(set.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.min_indexes.clear();
+    }
+
+    /// Returns `true` if the set contains a value.
+    ///
+    /// The value may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut set: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// set.insert(1);
+    /// assert_eq!(set.contains(&1), true);
+    /// assert_eq!(set.contains(&4), false);
+    /// ```
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(value).is_some()
+    }
+
+    /// Returns a reference to the value in the set, if any, that is equal to the given value.
+    ///
+    /// The value may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut set: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// set.insert(2);
+    /// assert_eq!(set.get(&2), Some(&2));
+    /// assert_eq!(set.get(&4), None);
+    /// ```
+    pub fn get<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let raw_idx = self.layout().find_raw_index(value).ok()?;
+        Some(&self.data[raw_idx])
+    }
+
+    /// Returns the rank of the value in the set if it exists (as `Result::Ok`),
+    /// or the rank of its largest predecessor plus one, if it does not exist (as `Result::Err`).
+    /// This is a constant-time operation.
+    ///
+    /// The value may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut set: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// set.insert(1);
+    /// set.insert(3);
+    /// assert_eq!(set.rank(&1), Ok(0));
+    /// assert_eq!(set.rank(&2), Err(1));
+    /// ```
+    pub fn rank<Q>(&self, value: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.layout().rank_by(|x| x.borrow().cmp(value))
+    }
+
+    /// Returns a reference to the value in the set, if any, with the given rank.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut set: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// set.insert(3);
+    /// set.insert(1);
+    /// assert_eq!(set.select(0), Some(&1));
+    /// assert_eq!(set.select(2), None);
+    /// ```
+    pub fn select(&self, rank: usize) -> Option<&T> {
+        self.layout().select(rank)
+    }
+
+    /// Returns a reference to the first (i.e., smallest) value in the set, if any.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut set: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// assert_eq!(set.first(), None);
+    /// set.insert(2);
+    /// set.insert(1);
+    /// assert_eq!(set.first(), Some(&1));
+    /// ```
+    pub fn first(&self) -> Option<&T> {
+        self.layout().first()
+    }
+
+    /// Returns a reference to the last (i.e., largest) value in the set, if any.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut set: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// assert_eq!(set.last(), None);
+    /// set.insert(1);
+    /// set.insert(2);
+    /// assert_eq!(set.last(), Some(&2));
+    /// ```
+    pub fn last(&self) -> Option<&T> {
+        self.layout().last()
+    }
+
+    /// Returns a reference to the greatest value in the set that is less than or equal to
+    /// the given value, if any.
+    ///
+    /// The value may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let set: ArrayRotatedSet<_, 8, 4> = vec![1, 3, 5].into_iter().collect();
+    /// assert_eq!(set.floor(&0), None);
+    /// assert_eq!(set.floor(&3), Some(&3));
+    /// assert_eq!(set.floor(&4), Some(&3));
+    /// ```
+    pub fn floor<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let layout = self.layout();
+        match layout.find_raw_index(value) {
+            Ok(raw_idx) => Some(&self.data[raw_idx]),
+            Err(insert_idx) => {
+                let rank = layout.get_logical_idx_from_raw_idx(insert_idx);
+                self.select(rank.checked_sub(1)?)
+            }
+        }
+    }
+
+    /// Returns a reference to the least value in the set that is greater than or equal to
+    /// the given value, if any.
+    ///
+    /// The value may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let set: ArrayRotatedSet<_, 8, 4> = vec![1, 3, 5].into_iter().collect();
+    /// assert_eq!(set.ceiling(&2), Some(&3));
+    /// assert_eq!(set.ceiling(&3), Some(&3));
+    /// assert_eq!(set.ceiling(&6), None);
+    /// ```
+    pub fn ceiling<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.layout().find_raw_index(value) {
+            Ok(raw_idx) => Some(&self.data[raw_idx]),
+            Err(insert_idx) => self.data.get(insert_idx),
+        }
+    }
+
+    /// Removes and returns the first (i.e., smallest) value in the set, if any.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut set: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// set.insert(2);
+    /// set.insert(1);
+    /// assert_eq!(set.pop_first(), Some(1));
+    /// assert_eq!(set.pop_first(), Some(2));
+    /// assert_eq!(set.pop_first(), None);
+    /// ```
+    pub fn pop_first(&mut self) -> Option<T> {
+        self.remove_at(0)
+    }
+
+    /// Removes and returns the last (i.e., largest) value in the set, if any.
+    ///
+    /// This has the same complexity as `RotatedArraySet::pop_last`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut set: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// set.insert(2);
+    /// set.insert(1);
+    /// assert_eq!(set.pop_last(), Some(2));
+    /// assert_eq!(set.pop_last(), Some(1));
+    /// assert_eq!(set.pop_last(), None);
+    /// ```
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.remove_at(self.data.len().checked_sub(1)?)
+    }
+
+    /// Adds a value to the set.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// If the set did not have this value present, `true` is returned.
+    ///
+    /// If the set did have this value present, `false` is returned, and the
+    /// entry is not updated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the set is full and doesn't have this value present.
+    /// See [`try_insert`] for a fallible version.
+    ///
+    /// [`try_insert`]: struct.ArrayRotatedSet.html#method.try_insert
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut set: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    ///
+    /// assert_eq!(set.insert(2), true);
+    /// assert_eq!(set.insert(2), false);
+    /// assert_eq!(set.len(), 1);
+    /// ```
+    pub fn insert(&mut self, value: T) -> bool {
+        match self.try_insert(value) {
+            Ok(inserted) => inserted,
+            Err(_) => panic!("ArrayRotatedSet is full"),
+        }
+    }
+
+    /// Adds a value to the set, unless the set is full.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// If the set did not have this value present and has room for it, `Ok(true)` is returned.
+    ///
+    /// If the set did have this value present, `Ok(false)` is returned, and the
+    /// entry is not updated (even if the set is full).
+    ///
+    /// If the set didn't have this value present but is full, the value is returned
+    /// in a `CapacityError`, and the set is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::{ArrayRotatedSet, CapacityError};
+    ///
+    /// let mut set: ArrayRotatedSet<_, 2, 2> = ArrayRotatedSet::new();
+    /// assert_eq!(set.try_insert(1), Ok(true));
+    /// assert_eq!(set.try_insert(2), Ok(true));
+    /// assert_eq!(set.try_insert(2), Ok(false));
+    /// assert_eq!(set.try_insert(3), Err(CapacityError(3)));
+    /// ```
+    pub fn try_insert(&mut self, value: T) -> Result<bool, CapacityError<T>> {
+        match self.layout().find_raw_index(&value) {
+            Ok(_) => Ok(false),
+            Err(_) if self.is_full() => Err(CapacityError(value)),
+            Err(insert_idx) => {
+                insert_raw(&mut self.data, &mut self.min_indexes, insert_idx, value);
+                Ok(true)
+            }
+        }
+    }
+
+    /// Adds all values from an iterator to the set, stopping at the first new value
+    /// that doesn't fit.
+    ///
+    /// Values already present in the set are not replaced, and only the first of several
+    /// equal values from the iterator is added, just as if the values had been inserted
+    /// in order. If the set fills up, the first value that could not be inserted is returned
+    /// in a `CapacityError`, and the values preceding it remain in the set.
+    ///
+    /// This is an `O(k√n)` operation for `k` values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::{ArrayRotatedSet, CapacityError};
+    ///
+    /// let mut set: ArrayRotatedSet<_, 4, 3> = ArrayRotatedSet::new();
+    /// assert_eq!(set.try_extend(vec![3, 1, 3, 2]), Ok(()));
+    /// assert_eq!(set.try_extend(vec![4, 5, 6]), Err(CapacityError(5)));
+    /// assert!(set.iter().eq([1, 2, 3, 4].iter()));
+    /// ```
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), CapacityError<T>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for value in iter {
+            self.try_insert(value)?;
+        }
+        Ok(())
+    }
+
+    /// Removes a value from the set. Returns whether the value was
+    /// present in the set.
+    ///
+    /// The value may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut set: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    ///
+    /// set.insert(2);
+    /// assert_eq!(set.remove(&2), true);
+    /// assert_eq!(set.remove(&2), false);
+    /// ```
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.take(value).is_some()
+    }
+
+    /// Removes and returns the value in the set, if any, that is equal to the given one.
+    ///
+    /// The value may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut set: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// set.insert(2);
+    /// assert_eq!(set.take(&2), Some(2));
+    /// assert_eq!(set.take(&2), None);
+    /// ```
+    pub fn take<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let raw_idx = self.layout().find_raw_index(value).ok()?;
+        Some(remove_raw(&mut self.data, &mut self.min_indexes, raw_idx))
+    }
+
+    /// Removes and returns the value in the set, if any, with the given rank.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut set: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// set.insert(1);
+    /// set.insert(2);
+    /// assert_eq!(set.remove_at(1), Some(2));
+    /// assert_eq!(set.remove_at(1), None);
+    /// ```
+    pub fn remove_at(&mut self, rank: usize) -> Option<T> {
+        if rank >= self.data.len() {
+            return None;
+        }
+        let raw_idx = self.layout().get_raw_idx_from_logical_idx(rank);
+        Some(remove_raw(&mut self.data, &mut self.min_indexes, raw_idx))
+    }
+
+    /// Retains only the values specified by the predicate.
+    ///
+    /// In other words, removes all values `v` for which `f(&v)` returns `false`.
+    /// The values are visited in ascending order. If `f` panics, the values it has not
+    /// visited yet remain in the set.
+    ///
+    /// This is a linear-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut set: ArrayRotatedSet<_, 8, 4> = (0..8).collect();
+    /// // Keep only the even numbers.
+    /// set.retain(|&k| k % 2 == 0);
+    /// assert!(set.iter().eq([0, 2, 4, 6].iter()));
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        // once the data array is sorted, we can compact it in place and rebuild the auxiliary array
+        self.sort_subarrays();
+        let len = self.data.len;
+        // the compactor owns the values until it is dropped
+        self.data.len = 0;
+        let mut compactor = Compactor {
+            set: self,
+            len,
+            visited: 0,
+            kept: 0,
+        };
+        let base = compactor.set.data.buf.as_mut_ptr() as *mut T;
+        while compactor.visited < len {
+            // SAFETY: the values from `visited` onward are initialized, and are only moved
+            // down to `kept`, which never exceeds `visited`.
+            unsafe {
+                let value = base.add(compactor.visited);
+                if f(&*value) {
+                    ptr::copy(value, base.add(compactor.kept), 1);
+                    compactor.kept += 1;
+                    compactor.visited += 1;
+                } else {
+                    // the value is no longer reachable, even if dropping it panics
+                    compactor.visited += 1;
+                    ptr::drop_in_place(value);
+                }
+            }
+        }
+    }
+
+    /// Returns the number of elements in the set.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut set: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// assert_eq!(set.len(), 0);
+    /// set.insert(1);
+    /// assert_eq!(set.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the set contains no elements.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut set: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// assert!(set.is_empty());
+    /// set.insert(1);
+    /// assert!(!set.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns `true` if the set holds as many elements as its capacity.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut set: ArrayRotatedSet<_, 1, 1> = ArrayRotatedSet::new();
+    /// assert!(!set.is_full());
+    /// set.insert(1);
+    /// assert!(set.is_full());
+    /// ```
+    pub fn is_full(&self) -> bool {
+        self.data.len() == N
+    }
+
+    /// Gets a double-ended iterator that visits the values in the set in ascending (descending) order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut set: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// set.insert(3);
+    /// set.insert(1);
+    /// set.insert(2);
+    /// assert!(set.iter().eq([1, 2, 3].iter()));
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(Range::new(self.layout()))
+    }
+
+    /// Gets a double-ended iterator over the values in the set as sorted slices,
+    /// in ascending order, as for `RotatedArraySet::chunks`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut set: ArrayRotatedSet<u32, 100, 14> = ArrayRotatedSet::new();
+    /// for i in (0..100).rev() {
+    ///     set.insert(i);
+    /// }
+    /// assert!(set.chunks().flatten().eq(set.iter()));
+    /// ```
+    pub fn chunks(&self) -> Chunks<'_, T> {
+        Chunks::new(Range::new(self.layout()))
+    }
+
+    /// Constructs a double-ended iterator over a sub-range of elements in the set,
+    /// specified as for `RotatedArraySet::range`.
+    ///
+    /// The bounds may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut set: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// set.insert(3);
+    /// set.insert(5);
+    /// set.insert(8);
+    /// assert!(set.range(4..=8).eq([5, 8].iter()));
+    /// ```
+    pub fn range<K, R>(&self, range: R) -> Iter<'_, T>
+    where
+        T: Borrow<K>,
+        K: Ord + ?Sized,
+        R: RangeBounds<K>,
+    {
+        Iter::new(self.layout().get_range(range))
+    }
+
+    /// Returns `true` if `self` has no elements in common with `other`.
+    /// This is equivalent to checking for an empty intersection.
+    ///
+    /// This has the same complexity as `RotatedArraySet::is_disjoint`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut a: ArrayRotatedSet<_, 4, 3> = ArrayRotatedSet::new();
+    /// a.insert(1);
+    /// let mut b: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// b.insert(2);
+    /// assert_eq!(a.is_disjoint(&b), true);
+    /// b.insert(1);
+    /// assert_eq!(a.is_disjoint(&b), false);
+    /// ```
+    pub fn is_disjoint<const M: usize, const R: usize>(
+        &self,
+        other: &ArrayRotatedSet<T, M, R>,
+    ) -> bool {
+        self.intersection(other).next().is_none()
+    }
+
+    /// Returns `true` if the set is a subset of another,
+    /// i.e., `other` contains at least all the values in `self`.
+    ///
+    /// This has the same complexity as `RotatedArraySet::is_subset`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut sup: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// sup.insert(1);
+    /// sup.insert(2);
+    /// let mut set: ArrayRotatedSet<_, 4, 3> = ArrayRotatedSet::new();
+    /// set.insert(2);
+    /// assert_eq!(set.is_subset(&sup), true);
+    /// set.insert(4);
+    /// assert_eq!(set.is_subset(&sup), false);
+    /// ```
+    pub fn is_subset<const M: usize, const R: usize>(
+        &self,
+        other: &ArrayRotatedSet<T, M, R>,
+    ) -> bool {
+        if self.len() > other.len() {
+            return false;
+        }
+        match (self.first(), self.last(), other.first(), other.last()) {
+            (None, ..) => true,
+            (Some(self_min), Some(self_max), Some(other_min), Some(other_max))
+                if self_min >= other_min && self_max <= other_max =>
+            {
+                self.difference(other).next().is_none()
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the set is a superset of another,
+    /// i.e., `self` contains at least all the values in `other`.
+    ///
+    /// This has the same complexity as [`is_subset`](#method.is_subset).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut sub: ArrayRotatedSet<_, 4, 3> = ArrayRotatedSet::new();
+    /// sub.insert(1);
+    /// let mut set: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// assert_eq!(set.is_superset(&sub), false);
+    /// set.insert(1);
+    /// assert_eq!(set.is_superset(&sub), true);
+    /// ```
+    pub fn is_superset<const M: usize, const R: usize>(
+        &self,
+        other: &ArrayRotatedSet<T, M, R>,
+    ) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Visits the values representing the difference,
+    /// i.e., the values that are in `self` but not in `other`,
+    /// in ascending order.
+    ///
+    /// This has the same complexity as `RotatedArraySet::difference`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut a: ArrayRotatedSet<_, 4, 3> = ArrayRotatedSet::new();
+    /// a.insert(1);
+    /// a.insert(2);
+    /// let mut b: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// b.insert(2);
+    /// b.insert(3);
+    /// assert!(a.difference(&b).eq([1].iter()));
+    /// ```
+    pub fn difference<'a, const M: usize, const R: usize>(
+        &'a self,
+        other: &'a ArrayRotatedSet<T, M, R>,
+    ) -> Difference<'a, T> {
+        Difference::new(self.layout(), other.layout())
+    }
+
+    /// Visits the values representing the symmetric difference,
+    /// i.e., the values that are in `self` or in `other` but not in both,
+    /// in ascending order.
+    ///
+    /// This has the same complexity as `RotatedArraySet::symmetric_difference`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut a: ArrayRotatedSet<_, 4, 3> = ArrayRotatedSet::new();
+    /// a.insert(1);
+    /// a.insert(2);
+    /// let mut b: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// b.insert(2);
+    /// b.insert(3);
+    /// assert!(a.symmetric_difference(&b).eq([1, 3].iter()));
+    /// ```
+    pub fn symmetric_difference<'a, const M: usize, const R: usize>(
+        &'a self,
+        other: &'a ArrayRotatedSet<T, M, R>,
+    ) -> SymmetricDifference<'a, T> {
+        SymmetricDifference::new(self.layout(), other.layout())
+    }
+
+    /// Visits the values representing the intersection,
+    /// i.e., the values that are both in `self` and `other`,
+    /// in ascending order.
+    ///
+    /// This has the same complexity as `RotatedArraySet::intersection`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut a: ArrayRotatedSet<_, 4, 3> = ArrayRotatedSet::new();
+    /// a.insert(1);
+    /// a.insert(2);
+    /// let mut b: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// b.insert(2);
+    /// b.insert(3);
+    /// assert!(a.intersection(&b).eq([2].iter()));
+    /// ```
+    pub fn intersection<'a, const M: usize, const R: usize>(
+        &'a self,
+        other: &'a ArrayRotatedSet<T, M, R>,
+    ) -> Intersection<'a, T> {
+        Intersection::new(self.layout(), other.layout())
+    }
+
+    /// Visits the values representing the union,
+    /// i.e., all the values in `self` or `other`, without duplicates,
+    /// in ascending order.
+    ///
+    /// This has the same complexity as `RotatedArraySet::union`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let mut a: ArrayRotatedSet<_, 4, 3> = ArrayRotatedSet::new();
+    /// a.insert(1);
+    /// a.insert(2);
+    /// let mut b: ArrayRotatedSet<_, 8, 4> = ArrayRotatedSet::new();
+    /// b.insert(2);
+    /// b.insert(3);
+    /// assert!(a.union(&b).eq([1, 2, 3].iter()));
+    /// ```
+    pub fn union<'a, const M: usize, const R: usize>(
+        &'a self,
+        other: &'a ArrayRotatedSet<T, M, R>,
+    ) -> Union<'a, T> {
+        Union::new(self.layout(), other.layout())
+    }
+
+    fn layout(&self) -> Layout<'_, T> {
+        Layout::new(&self.data, &self.min_indexes)
+    }
+
+    // Builds a set from an iterator over strictly ascending values in linear time.
+    fn from_sorted_dedup_iter<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = T>,
+    {
+        let mut this = ArrayRotatedSet::new();
+        for value in iter {
+            if this.is_full() {
+                panic!("ArrayRotatedSet is full");
+            }
+            this.data.push(value);
+        }
+        this.init_min_indexes();
+        debug_assert!(this.layout().assert_invariants());
+        this
+    }
+
+    // given sorted data array, initialize auxiliary array
+    fn init_min_indexes(&mut self) {
+        self.min_indexes.clear();
+        for _ in 0..subarray_count(self.data.len()) {
+            self.min_indexes.push(0);
+        }
+    }
+
+    // rotate all subarrays into sorted order, so that raw and logical indexes coincide
+    fn sort_subarrays(&mut self) {
+        let subarray_count = self.min_indexes.len();
+        sort_subarrays(&mut self.data, &mut self.min_indexes, 0, subarray_count);
+    }
+}
+
+// Compacts the retained values of a sorted data array towards its front as they are visited.
+// When dropped, even if the predicate panicked, it moves the unvisited values down behind
+// the retained ones and rebuilds the auxiliary array, so the set is left valid.
+struct Compactor<'a, T, const N: usize, const S: usize>
+where
+    T: Ord,
+{
+    set: &'a mut ArrayRotatedSet<T, N, S>,
+    len: usize,
+    visited: usize,
+    kept: usize,
+}
+
+impl<T, const N: usize, const S: usize> Drop for Compactor<'_, T, N, S>
+where
+    T: Ord,
+{
+    fn drop(&mut self) {
+        let unvisited = self.len - self.visited;
+        let base = self.set.data.buf.as_mut_ptr() as *mut T;
+        // SAFETY: the retained values are initialized up to `kept`, and the unvisited values
+        // from `visited` onward, so moving the latter down leaves a contiguous prefix.
+        unsafe {
+            ptr::copy(base.add(self.visited), base.add(self.kept), unvisited);
+        }
+        self.set.data.len = self.kept + unvisited;
+        self.set.init_min_indexes();
+    }
+}
+
+impl<T, const N: usize, const S: usize> PartialEq for ArrayRotatedSet<T, N, S>
+where
+    T: Ord,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T, const N: usize, const S: usize> Eq for ArrayRotatedSet<T, N, S> where T: Ord {}
+
+impl<T, const N: usize, const S: usize> Hash for ArrayRotatedSet<T, N, S>
+where
+    T: Ord + Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for value in self.iter() {
+            value.hash(state);
+        }
+    }
+}
+
+impl<T, const N: usize, const S: usize> PartialOrd for ArrayRotatedSet<T, N, S>
+where
+    T: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T, const N: usize, const S: usize> Ord for ArrayRotatedSet<T, N, S>
+where
+    T: Ord,
+{
+    /// Compares two sets lexicographically by their values in ascending order,
+    /// like `BTreeSet`.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<'a, T, const N: usize, const S: usize> IntoIterator for &'a ArrayRotatedSet<T, N, S>
+where
+    T: Ord,
+{
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T, const N: usize, const S: usize> Default for ArrayRotatedSet<T, N, S>
+where
+    T: Ord,
+{
+    fn default() -> ArrayRotatedSet<T, N, S> {
+        ArrayRotatedSet::new()
+    }
+}
+
+impl<T, const N: usize, const S: usize> IntoIterator for ArrayRotatedSet<T, N, S>
+where
+    T: Ord,
+{
+    type Item = T;
+    type IntoIter = ArrayIntoIter<T, N>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        self.sort_subarrays();
+        let len = self.data.len;
+        // the iterator takes ownership of the values
+        self.data.len = 0;
+        ArrayIntoIter {
+            buf: mem::replace(&mut self.data.buf, [InlineVec::<T, N>::UNINIT; N]),
+            alive: 0..len,
+        }
+    }
+}
+
+impl<T, const N: usize, const S: usize> FromIterator<T> for ArrayRotatedSet<T, N, S>
+where
+    T: Ord,
+{
+    /// Builds a set from an iterator. If an element occurs more than once,
+    /// the set keeps its first occurrence, just as if the elements had been inserted in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator yields more distinct values than the set can hold.
+    /// See [`try_extend`] for a fallible version.
+    ///
+    /// [`try_extend`]: struct.ArrayRotatedSet.html#method.try_extend
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = ArrayRotatedSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T, const N: usize, const S: usize> Extend<T> for ArrayRotatedSet<T, N, S>
+where
+    T: Ord,
+{
+    /// Adds all values from an iterator. Values already present in the set are not replaced,
+    /// and only the first of several equal values from the iterator is added, just as if the
+    /// values had been inserted in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the set fills up before all new values are added.
+    /// See [`try_extend`] for a fallible version.
+    ///
+    /// [`try_extend`]: struct.ArrayRotatedSet.html#method.try_extend
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<'a, T, const N: usize, const S: usize> Extend<&'a T> for ArrayRotatedSet<T, N, S>
+where
+    T: 'a + Ord + Copy,
+{
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned());
+    }
+}
+
+impl<T, const N: usize, const S: usize> Sub<&ArrayRotatedSet<T, N, S>> for &ArrayRotatedSet<T, N, S>
+where
+    T: Ord + Clone,
+{
+    type Output = ArrayRotatedSet<T, N, S>;
+
+    /// Returns the difference of `self` and `rhs` as a new `ArrayRotatedSet`.
+    ///
+    /// This is a linear-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let a: ArrayRotatedSet<_, 8, 4> = vec![1, 2, 3].into_iter().collect();
+    /// let b: ArrayRotatedSet<_, 8, 4> = vec![3, 4, 5].into_iter().collect();
+    ///
+    /// let result = &a - &b;
+    /// assert!(result.iter().eq([1, 2].iter()));
+    /// ```
+    fn sub(self, rhs: &ArrayRotatedSet<T, N, S>) -> ArrayRotatedSet<T, N, S> {
+        ArrayRotatedSet::from_sorted_dedup_iter(self.difference(rhs).cloned())
+    }
+}
+
+impl<T, const N: usize, const S: usize> BitXor<&ArrayRotatedSet<T, N, S>>
+    for &ArrayRotatedSet<T, N, S>
+where
+    T: Ord + Clone,
+{
+    type Output = ArrayRotatedSet<T, N, S>;
+
+    /// Returns the symmetric difference of `self` and `rhs` as a new `ArrayRotatedSet`.
+    ///
+    /// This is a linear-time operation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the symmetric difference has more values than the set can hold.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let a: ArrayRotatedSet<_, 8, 4> = vec![1, 2, 3].into_iter().collect();
+    /// let b: ArrayRotatedSet<_, 8, 4> = vec![2, 3, 4].into_iter().collect();
+    ///
+    /// let result = &a ^ &b;
+    /// assert!(result.iter().eq([1, 4].iter()));
+    /// ```
+    fn bitxor(self, rhs: &ArrayRotatedSet<T, N, S>) -> ArrayRotatedSet<T, N, S> {
+        ArrayRotatedSet::from_sorted_dedup_iter(self.symmetric_difference(rhs).cloned())
+    }
+}
+
+impl<T, const N: usize, const S: usize> BitAnd<&ArrayRotatedSet<T, N, S>>
+    for &ArrayRotatedSet<T, N, S>
+where
+    T: Ord + Clone,
+{
+    type Output = ArrayRotatedSet<T, N, S>;
+
+    /// Returns the intersection of `self` and `rhs` as a new `ArrayRotatedSet`.
+    ///
+    /// This is a linear-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let a: ArrayRotatedSet<_, 8, 4> = vec![1, 2, 3].into_iter().collect();
+    /// let b: ArrayRotatedSet<_, 8, 4> = vec![2, 3, 4].into_iter().collect();
+    ///
+    /// let result = &a & &b;
+    /// assert!(result.iter().eq([2, 3].iter()));
+    /// ```
+    fn bitand(self, rhs: &ArrayRotatedSet<T, N, S>) -> ArrayRotatedSet<T, N, S> {
+        ArrayRotatedSet::from_sorted_dedup_iter(self.intersection(rhs).cloned())
+    }
+}
+
+impl<T, const N: usize, const S: usize> BitOr<&ArrayRotatedSet<T, N, S>>
+    for &ArrayRotatedSet<T, N, S>
+where
+    T: Ord + Clone,
+{
+    type Output = ArrayRotatedSet<T, N, S>;
+
+    /// Returns the union of `self` and `rhs` as a new `ArrayRotatedSet`.
+    ///
+    /// This is a linear-time operation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the union has more values than the set can hold.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::ArrayRotatedSet;
+    ///
+    /// let a: ArrayRotatedSet<_, 8, 4> = vec![1, 2, 3].into_iter().collect();
+    /// let b: ArrayRotatedSet<_, 8, 4> = vec![3, 4, 5].into_iter().collect();
+    ///
+    /// let result = &a | &b;
+    /// assert!(result.iter().eq([1, 2, 3, 4, 5].iter()));
+    /// ```
+    fn bitor(self, rhs: &ArrayRotatedSet<T, N, S>) -> ArrayRotatedSet<T, N, S> {
+        ArrayRotatedSet::from_sorted_dedup_iter(self.union(rhs).cloned())
+    }
+}
+
+/// An owning iterator over the items of an `ArrayRotatedSet`.
+///
+/// This `struct` is created by the [`into_iter`] method on [`ArrayRotatedSet`]
+/// (provided by the `IntoIterator` trait). See its documentation for more.
+///
+/// [`ArrayRotatedSet`]: struct.ArrayRotatedSet.html
+/// [`into_iter`]: struct.ArrayRotatedSet.html#method.into_iter
+pub struct ArrayIntoIter<T, const N: usize> {
+    buf: [MaybeUninit<T>; N],
+    // the elements of `buf` in this range are initialized, and have not been yielded yet
+    alive: core::ops::Range<usize>,
+}
+
+impl<T, const N: usize> ArrayIntoIter<T, N> {
+    fn as_slice(&self) -> &[T] {
+        // SAFETY: the elements in `alive` are initialized.
+        unsafe {
+            slice::from_raw_parts(
+                (self.buf.as_ptr() as *const T).add(self.alive.start),
+                self.alive.len(),
+            )
+        }
+    }
+}
+
+impl<T, const N: usize> Iterator for ArrayIntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let idx = self.alive.next()?;
+        // SAFETY: the element was initialized, and is no longer reachable.
+        Some(unsafe { self.buf[idx].as_ptr().read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.alive.size_hint()
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for ArrayIntoIter<T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        let idx = self.alive.next_back()?;
+        // SAFETY: the element was initialized, and is no longer reachable.
+        Some(unsafe { self.buf[idx].as_ptr().read() })
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for ArrayIntoIter<T, N> {}
+
+impl<T, const N: usize> FusedIterator for ArrayIntoIter<T, N> {}
+
+impl<T, const N: usize> Drop for ArrayIntoIter<T, N> {
+    fn drop(&mut self) {
+        let alive = mem::replace(&mut self.alive, 0..0);
+        // SAFETY: the elements in `alive` were initialized, and are no longer reachable.
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                (self.buf.as_mut_ptr() as *mut T).add(alive.start),
+                alive.len(),
+            ));
+        }
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for ArrayIntoIter<T, N> {
+    fn clone(&self) -> Self {
+        let mut clone = InlineVec::<T, N>::new();
+        for value in self.as_slice() {
+            clone.push(value.clone());
+        }
+        let len = clone.len;
+        // the iterator takes ownership of the values
+        clone.len = 0;
+        ArrayIntoIter {
+            buf: mem::replace(&mut clone.buf, [InlineVec::<T, N>::UNINIT; N]),
+            alive: 0..len,
+        }
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArrayIntoIter<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ArrayIntoIter")
+            .field(&self.as_slice())
+            .finish()
+    }
+}
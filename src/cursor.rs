@@ -0,0 +1,642 @@
+//! Cursors over a `RotatedArraySet`, which can be positioned by rank or by value
+//! and then moved in either direction in constant time.
+//!
+//! A cursor caches the location of its current element (its subarray and that subarray's
+//! bounds), in the spirit of the "locator" of Munro and Suwanda's structure, so stepping
+//! to a neighboring element never has to map a rank to a subarray from scratch.
+
+use core::borrow::Borrow;
+use core::ops::Bound::{self, Excluded, Included, Unbounded};
+
+use crate::{RotatedArraySet, SubarrayBounds};
+
+// The location of an element, identified by its logical index (i.e., rank), together with
+// the subarray containing it. The "ghost" position past the last element has index `len()`.
+#[derive(Debug, Clone, Copy)]
+struct Locator {
+    index: usize,
+    subarray: SubarrayBounds,
+}
+
+impl Locator {
+    fn new<T: Ord>(set: &RotatedArraySet<T>, index: usize) -> Self {
+        debug_assert!(index <= set.len());
+        if index == set.len() {
+            return Self::ghost(set);
+        }
+        Locator {
+            index,
+            subarray: set.layout().subarray_bounds_of(index),
+        }
+    }
+
+    fn ghost<T: Ord>(set: &RotatedArraySet<T>) -> Self {
+        Locator {
+            index: set.len(),
+            subarray: SubarrayBounds {
+                idx: set.min_indexes.len(),
+                start: set.len(),
+                len: 0,
+            },
+        }
+    }
+
+    fn is_ghost<T: Ord>(&self, set: &RotatedArraySet<T>) -> bool {
+        self.index == set.len()
+    }
+
+    fn raw_idx<T: Ord>(&self, set: &RotatedArraySet<T>) -> usize {
+        debug_assert!(!self.is_ghost(set));
+        let SubarrayBounds { idx, start, len } = self.subarray;
+        let pivot_offset = set.min_indexes[idx];
+        start + (pivot_offset + self.index - start) % len
+    }
+
+    fn get<'a, T: Ord>(&self, set: &'a RotatedArraySet<T>) -> Option<&'a T> {
+        if self.is_ghost(set) {
+            None
+        } else {
+            Some(&set.data[self.raw_idx(set)])
+        }
+    }
+
+    // Returns the locator of the next element, wrapping from the ghost to the first element.
+    fn next<T: Ord>(&self, set: &RotatedArraySet<T>) -> Self {
+        if self.is_ghost(set) {
+            return Self::new(set, 0);
+        }
+        let index = self.index + 1;
+        let subarray = if index == set.len() {
+            return Self::ghost(set);
+        } else if index == self.subarray.start + self.subarray.len {
+            set.layout().next_subarray_bounds(self.subarray)
+        } else {
+            self.subarray
+        };
+        Locator { index, subarray }
+    }
+
+    // Returns the locator of the previous element, wrapping from the first element to the ghost.
+    fn prev<T: Ord>(&self, set: &RotatedArraySet<T>) -> Self {
+        if self.index == 0 {
+            return Self::ghost(set);
+        }
+        let index = self.index - 1;
+        let subarray = if self.is_ghost(set) {
+            set.layout().subarray_bounds(set.min_indexes.len() - 1)
+        } else if index < self.subarray.start {
+            set.layout().prev_subarray_bounds(self.subarray)
+        } else {
+            self.subarray
+        };
+        Locator { index, subarray }
+    }
+}
+
+/// A cursor over a `RotatedArraySet`.
+///
+/// A `Cursor` is like an iterator, except that it can freely seek back-and-forth.
+///
+/// A cursor always points to an element of the set, or to a "ghost" non-element
+/// that yields `None` and sits between the last and first elements, so that the
+/// cursor indexes in a logically circular way.
+///
+/// Moving the cursor or peeking at a neighboring element is a constant-time operation.
+///
+/// This `struct` is created by the [`cursor_at`], [`lower_bound`] and [`upper_bound`]
+/// methods on [`RotatedArraySet`].
+///
+/// [`RotatedArraySet`]: struct.RotatedArraySet.html
+/// [`cursor_at`]: struct.RotatedArraySet.html#method.cursor_at
+/// [`lower_bound`]: struct.RotatedArraySet.html#method.lower_bound
+/// [`upper_bound`]: struct.RotatedArraySet.html#method.upper_bound
+#[derive(Debug)]
+pub struct Cursor<'a, T: 'a> {
+    set: &'a RotatedArraySet<T>,
+    locator: Locator,
+}
+
+// Derived `Copy` and `Clone` would require `T: Copy`, but we only hold a reference.
+impl<T> Clone for Cursor<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Cursor<'_, T> {}
+
+/// A cursor over a `RotatedArraySet` with editing operations.
+///
+/// A `CursorMut` is like a [`Cursor`], except that it can also remove the current element,
+/// and insert new elements next to it as long as they keep the set in order.
+///
+/// This `struct` is created by the [`cursor_at_mut`], [`lower_bound_mut`] and
+/// [`upper_bound_mut`] methods on [`RotatedArraySet`].
+///
+/// [`Cursor`]: struct.Cursor.html
+/// [`RotatedArraySet`]: struct.RotatedArraySet.html
+/// [`cursor_at_mut`]: struct.RotatedArraySet.html#method.cursor_at_mut
+/// [`lower_bound_mut`]: struct.RotatedArraySet.html#method.lower_bound_mut
+/// [`upper_bound_mut`]: struct.RotatedArraySet.html#method.upper_bound_mut
+#[derive(Debug)]
+pub struct CursorMut<'a, T: 'a> {
+    set: &'a mut RotatedArraySet<T>,
+    locator: Locator,
+}
+
+impl<T> RotatedArraySet<T>
+where
+    T: Ord,
+{
+    /// Returns a cursor pointing at the element with the given rank,
+    /// or at the "ghost" non-element if `rank == self.len()`.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rank > self.len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set: RotatedArraySet<_> = vec![1, 2, 3].into();
+    /// let mut cursor = set.cursor_at(1);
+    /// assert_eq!(cursor.current(), Some(&2));
+    /// cursor.move_next();
+    /// assert_eq!(cursor.current(), Some(&3));
+    /// ```
+    pub fn cursor_at(&self, rank: usize) -> Cursor<'_, T> {
+        assert!(
+            rank <= self.len(),
+            "rank is out of bounds in RotatedArraySet"
+        );
+        Cursor {
+            set: self,
+            locator: Locator::new(self, rank),
+        }
+    }
+
+    /// Returns a cursor pointing at the first element that is above the given bound,
+    /// or at the "ghost" non-element if no such element exists.
+    ///
+    /// Passing `Bound::Unbounded` will return a cursor pointing at the first element of the set.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    /// use std::ops::Bound;
+    ///
+    /// let set: RotatedArraySet<_> = vec![1, 2, 3, 4].into();
+    /// assert_eq!(set.lower_bound(Bound::Included(&2)).current(), Some(&2));
+    /// assert_eq!(set.lower_bound(Bound::Excluded(&2)).current(), Some(&3));
+    /// assert_eq!(set.lower_bound(Bound::Excluded(&4)).current(), None);
+    /// ```
+    pub fn lower_bound<Q>(&self, bound: Bound<&Q>) -> Cursor<'_, T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.cursor_at(self.lower_bound_rank(bound))
+    }
+
+    /// Returns a cursor pointing at the last element that is below the given bound,
+    /// or at the "ghost" non-element if no such element exists.
+    ///
+    /// Passing `Bound::Unbounded` will return a cursor pointing at the last element of the set.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    /// use std::ops::Bound;
+    ///
+    /// let set: RotatedArraySet<_> = vec![1, 2, 3, 4].into();
+    /// assert_eq!(set.upper_bound(Bound::Included(&3)).current(), Some(&3));
+    /// assert_eq!(set.upper_bound(Bound::Excluded(&3)).current(), Some(&2));
+    /// assert_eq!(set.upper_bound(Bound::Excluded(&1)).current(), None);
+    /// ```
+    pub fn upper_bound<Q>(&self, bound: Bound<&Q>) -> Cursor<'_, T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.cursor_at(self.upper_bound_rank(bound))
+    }
+
+    /// Returns a mutable cursor pointing at the element with the given rank,
+    /// or at the "ghost" non-element if `rank == self.len()`.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rank > self.len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut set: RotatedArraySet<_> = vec![1, 2, 3].into();
+    /// let mut cursor = set.cursor_at_mut(0);
+    /// assert_eq!(cursor.remove_current(), Some(1));
+    /// assert_eq!(cursor.current(), Some(&2));
+    /// ```
+    pub fn cursor_at_mut(&mut self, rank: usize) -> CursorMut<'_, T> {
+        assert!(
+            rank <= self.len(),
+            "rank is out of bounds in RotatedArraySet"
+        );
+        let locator = Locator::new(self, rank);
+        CursorMut { set: self, locator }
+    }
+
+    /// Returns a mutable cursor pointing at the first element that is above the given bound,
+    /// or at the "ghost" non-element if no such element exists.
+    ///
+    /// Passing `Bound::Unbounded` will return a cursor pointing at the first element of the set.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    /// use std::ops::Bound;
+    ///
+    /// let mut set: RotatedArraySet<_> = vec![1, 2, 4].into();
+    /// let mut cursor = set.lower_bound_mut(Bound::Excluded(&2));
+    /// assert_eq!(cursor.insert_before(3), Ok(()));
+    /// assert!(set.iter().eq([1, 2, 3, 4].iter()));
+    /// ```
+    pub fn lower_bound_mut<Q>(&mut self, bound: Bound<&Q>) -> CursorMut<'_, T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let rank = self.lower_bound_rank(bound);
+        self.cursor_at_mut(rank)
+    }
+
+    /// Returns a mutable cursor pointing at the last element that is below the given bound,
+    /// or at the "ghost" non-element if no such element exists.
+    ///
+    /// Passing `Bound::Unbounded` will return a cursor pointing at the last element of the set.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    /// use std::ops::Bound;
+    ///
+    /// let mut set: RotatedArraySet<_> = vec![1, 2, 3, 4].into();
+    /// let mut cursor = set.upper_bound_mut(Bound::Unbounded);
+    /// assert_eq!(cursor.remove_current(), Some(4));
+    /// assert_eq!(set.len(), 3);
+    /// ```
+    pub fn upper_bound_mut<Q>(&mut self, bound: Bound<&Q>) -> CursorMut<'_, T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let rank = self.upper_bound_rank(bound);
+        self.cursor_at_mut(rank)
+    }
+
+    // Returns the rank of the first element above the bound, or `len()` if there is none.
+    fn lower_bound_rank<Q>(&self, bound: Bound<&Q>) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let raw_idx = match bound {
+            Unbounded => return 0,
+            Included(value) => self.layout().lower_bound_by_key(value, |x| x.borrow()),
+            Excluded(value) => self.layout().upper_bound_by_key(value, |x| x.borrow()),
+        };
+        self.layout().get_logical_idx_from_raw_idx(raw_idx)
+    }
+
+    // Returns the rank of the last element below the bound, or `len()` if there is none.
+    fn upper_bound_rank<Q>(&self, bound: Bound<&Q>) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let raw_idx = match bound {
+            Unbounded => self.len(),
+            Included(value) => self.layout().upper_bound_by_key(value, |x| x.borrow()),
+            Excluded(value) => self.layout().lower_bound_by_key(value, |x| x.borrow()),
+        };
+        match self.layout().get_logical_idx_from_raw_idx(raw_idx) {
+            0 => self.len(),
+            end => end - 1,
+        }
+    }
+}
+
+impl<'a, T> Cursor<'a, T>
+where
+    T: Ord,
+{
+    /// Returns the rank of the element the cursor is currently pointing to,
+    /// or `None` if the cursor is pointing to the "ghost" non-element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set: RotatedArraySet<_> = vec![1, 2, 3].into();
+    /// let mut cursor = set.cursor_at(2);
+    /// assert_eq!(cursor.index(), Some(2));
+    /// cursor.move_next();
+    /// assert_eq!(cursor.index(), None);
+    /// ```
+    pub fn index(&self) -> Option<usize> {
+        if self.locator.is_ghost(self.set) {
+            None
+        } else {
+            Some(self.locator.index)
+        }
+    }
+
+    /// Returns a reference to the element the cursor is currently pointing to,
+    /// or `None` if the cursor is pointing to the "ghost" non-element.
+    pub fn current(&self) -> Option<&'a T> {
+        self.locator.get(self.set)
+    }
+
+    /// Moves the cursor to the next element of the set.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the first element of the set. If it is pointing to the last element of the set
+    /// then this will move it to the "ghost" non-element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set: RotatedArraySet<_> = vec![1, 2].into();
+    /// let mut cursor = set.cursor_at(1);
+    /// cursor.move_next();
+    /// assert_eq!(cursor.current(), None);
+    /// cursor.move_next();
+    /// assert_eq!(cursor.current(), Some(&1));
+    /// ```
+    pub fn move_next(&mut self) {
+        self.locator = self.locator.next(self.set);
+    }
+
+    /// Moves the cursor to the previous element of the set.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the last element of the set. If it is pointing to the first element of the set
+    /// then this will move it to the "ghost" non-element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set: RotatedArraySet<_> = vec![1, 2].into();
+    /// let mut cursor = set.cursor_at(0);
+    /// cursor.move_prev();
+    /// assert_eq!(cursor.current(), None);
+    /// cursor.move_prev();
+    /// assert_eq!(cursor.current(), Some(&2));
+    /// ```
+    pub fn move_prev(&mut self) {
+        self.locator = self.locator.prev(self.set);
+    }
+
+    /// Returns a reference to the next element, without moving the cursor.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the first element of the set. If it is pointing to the last element of the set
+    /// then this returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set: RotatedArraySet<_> = vec![1, 2].into();
+    /// let cursor = set.cursor_at(0);
+    /// assert_eq!(cursor.peek_next(), Some(&2));
+    /// assert_eq!(cursor.current(), Some(&1));
+    /// ```
+    pub fn peek_next(&self) -> Option<&'a T> {
+        self.locator.next(self.set).get(self.set)
+    }
+
+    /// Returns a reference to the previous element, without moving the cursor.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the last element of the set. If it is pointing to the first element of the set
+    /// then this returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set: RotatedArraySet<_> = vec![1, 2].into();
+    /// let cursor = set.cursor_at(2);
+    /// assert_eq!(cursor.peek_prev(), Some(&2));
+    /// assert_eq!(cursor.current(), None);
+    /// ```
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        self.locator.prev(self.set).get(self.set)
+    }
+}
+
+impl<'a, T> CursorMut<'a, T>
+where
+    T: Ord,
+{
+    /// Returns the rank of the element the cursor is currently pointing to,
+    /// or `None` if the cursor is pointing to the "ghost" non-element.
+    pub fn index(&self) -> Option<usize> {
+        self.as_cursor().index()
+    }
+
+    /// Returns a reference to the element the cursor is currently pointing to,
+    /// or `None` if the cursor is pointing to the "ghost" non-element.
+    pub fn current(&self) -> Option<&T> {
+        self.locator.get(self.set)
+    }
+
+    /// Moves the cursor to the next element of the set.
+    ///
+    /// See [`Cursor::move_next`] for details.
+    ///
+    /// [`Cursor::move_next`]: struct.Cursor.html#method.move_next
+    pub fn move_next(&mut self) {
+        self.locator = self.locator.next(self.set);
+    }
+
+    /// Moves the cursor to the previous element of the set.
+    ///
+    /// See [`Cursor::move_prev`] for details.
+    ///
+    /// [`Cursor::move_prev`]: struct.Cursor.html#method.move_prev
+    pub fn move_prev(&mut self) {
+        self.locator = self.locator.prev(self.set);
+    }
+
+    /// Returns a reference to the next element, without moving the cursor.
+    ///
+    /// See [`Cursor::peek_next`] for details.
+    ///
+    /// [`Cursor::peek_next`]: struct.Cursor.html#method.peek_next
+    pub fn peek_next(&self) -> Option<&T> {
+        self.locator.next(self.set).get(self.set)
+    }
+
+    /// Returns a reference to the previous element, without moving the cursor.
+    ///
+    /// See [`Cursor::peek_prev`] for details.
+    ///
+    /// [`Cursor::peek_prev`]: struct.Cursor.html#method.peek_prev
+    pub fn peek_prev(&self) -> Option<&T> {
+        self.locator.prev(self.set).get(self.set)
+    }
+
+    /// Returns a read-only cursor pointing to the current element.
+    ///
+    /// The lifetime of the returned `Cursor` is bound to that of the `CursorMut`,
+    /// which means it cannot outlive the `CursorMut`, and that the `CursorMut`
+    /// is frozen for the lifetime of the `Cursor`.
+    pub fn as_cursor(&self) -> Cursor<'_, T> {
+        Cursor {
+            set: self.set,
+            locator: self.locator,
+        }
+    }
+
+    /// Removes the current element from the set, and returns it.
+    /// The cursor is then moved to point to the next element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then no element is removed,
+    /// and `None` is returned.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut set: RotatedArraySet<_> = (1..=6).collect();
+    /// // remove every other element
+    /// let mut cursor = set.cursor_at_mut(0);
+    /// while cursor.current().is_some() {
+    ///     cursor.move_next();
+    ///     cursor.remove_current();
+    /// }
+    /// assert!(set.iter().eq([1, 3, 5].iter()));
+    /// ```
+    pub fn remove_current(&mut self) -> Option<T> {
+        if self.locator.is_ghost(self.set) {
+            return None;
+        }
+        let value = self.set.remove_raw(self.locator.raw_idx(self.set));
+        // the removal shifts elements between subarrays, so locate the next element afresh
+        self.locator = Locator::new(self.set, self.locator.index);
+        Some(value)
+    }
+
+    /// Inserts a new element into the set immediately before the current one
+    /// (or at the end of the set, if the cursor is pointing to the "ghost" non-element).
+    /// The cursor keeps pointing to the same element.
+    ///
+    /// If the new element would not be strictly between the previous and current elements,
+    /// it is not inserted, and is returned as `Err`.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut set: RotatedArraySet<_> = vec![1, 3].into();
+    /// let mut cursor = set.cursor_at_mut(1);
+    /// assert_eq!(cursor.insert_before(2), Ok(()));
+    /// assert_eq!(cursor.insert_before(4), Err(4));
+    /// assert_eq!(cursor.current(), Some(&3));
+    /// assert!(set.iter().eq([1, 2, 3].iter()));
+    /// ```
+    pub fn insert_before(&mut self, value: T) -> Result<(), T> {
+        let in_order = self.peek_prev().map_or(true, |prev| *prev < value)
+            && self.current().map_or(true, |next| value < *next);
+        if !in_order {
+            return Err(value);
+        }
+        let index = self.locator.index;
+        self.insert_at(index, value);
+        self.locator = Locator::new(self.set, index + 1);
+        Ok(())
+    }
+
+    /// Inserts a new element into the set immediately after the current one
+    /// (or at the start of the set, if the cursor is pointing to the "ghost" non-element).
+    /// The cursor keeps pointing to the same element.
+    ///
+    /// If the new element would not be strictly between the current and next elements,
+    /// it is not inserted, and is returned as `Err`.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut set: RotatedArraySet<_> = vec![1, 3].into();
+    /// let mut cursor = set.cursor_at_mut(0);
+    /// assert_eq!(cursor.insert_after(2), Ok(()));
+    /// assert_eq!(cursor.insert_after(0), Err(0));
+    /// assert_eq!(cursor.current(), Some(&1));
+    /// assert!(set.iter().eq([1, 2, 3].iter()));
+    /// ```
+    pub fn insert_after(&mut self, value: T) -> Result<(), T> {
+        let in_order = self.current().map_or(true, |prev| *prev < value)
+            && self.peek_next().map_or(true, |next| value < *next);
+        if !in_order {
+            return Err(value);
+        }
+        if self.locator.is_ghost(self.set) {
+            self.insert_at(0, value);
+            self.locator = Locator::ghost(self.set);
+        } else {
+            let index = self.locator.index;
+            self.insert_at(index + 1, value);
+            self.locator = Locator::new(self.set, index);
+        }
+        Ok(())
+    }
+
+    // Inserts a value so that it has the given rank.
+    fn insert_at(&mut self, rank: usize, value: T) {
+        // the raw insertion point is the raw index of the element the value is inserted before
+        let raw_idx = if rank == self.set.len() {
+            rank
+        } else {
+            self.set.layout().get_raw_idx_from_logical_idx(rank)
+        };
+        self.set.insert_raw(raw_idx, value);
+    }
+}
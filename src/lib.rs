@@ -1,4 +1,4 @@
-//! An ordered set based on a 2-level rotated array.
+//! An ordered set, multiset and map based on a 2-level rotated array.
 //!
 //! See <a href="https://github.com/senderista/rotated-array-set/blob/master/README.md">the repository README</a> for a detailed discussion of this collection's performance
 //! benefits and drawbacks.
@@ -7,18 +7,41 @@
 #![doc(
     html_logo_url = "https://raw.githubusercontent.com/senderista/rotated-array-set/master/img/cells.png"
 )]
-
-use std::cmp::Ordering::{self, Equal, Greater, Less};
-use std::cmp::{max, min};
-use std::fmt::Debug;
-use std::hash::{Hash, Hasher};
-use std::iter::{DoubleEndedIterator, ExactSizeIterator, FromIterator, FusedIterator, Peekable};
-use std::mem;
-use std::ops::Bound::{Excluded, Included, Unbounded};
-use std::ops::RangeBounds;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::collections::{TryReserveError, VecDeque};
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering::{self, Equal, Greater, Less};
+use core::cmp::{max, min};
+use core::hash::{Hash, Hasher};
+use core::iter::{DoubleEndedIterator, ExactSizeIterator, FromIterator, FusedIterator};
+use core::mem;
+use core::ops::Bound::{Excluded, Included, Unbounded};
+use core::ops::{BitAnd, BitOr, BitXor, DerefMut, RangeBounds, Sub};
 // remove when Iterator::is_sorted is stabilized
 use is_sorted::IsSorted;
 
+mod array_set;
+mod cursor;
+pub mod map;
+mod multiset;
+#[cfg(feature = "rand")]
+mod random;
+#[cfg(feature = "serde")]
+mod serde_impls;
+pub mod view;
+
+pub use crate::array_set::{subarray_count, ArrayIntoIter, ArrayRotatedSet, CapacityError};
+pub use crate::cursor::{Cursor, CursorMut};
+pub use crate::map::RotatedArrayMap;
+pub use crate::multiset::RotatedArrayMultiset;
+#[cfg(feature = "rand")]
+pub use crate::random::{Sample, WeightedSampler};
+pub use crate::view::{FixedWidth, RotatedArraySetView};
+
 /// An ordered set based on a 2-level rotated array.
 ///
 /// # Examples
@@ -53,23 +76,48 @@ use is_sorted::IsSorted;
 pub struct RotatedArraySet<T> {
     data: Vec<T>,
     min_indexes: Vec<usize>,
-    min_data: Vec<T>,
 }
 
+// A borrowed view of the data and auxiliary arrays. All searches and index translations
+// are implemented on this view, so that they can be shared by any storage of the layout.
+#[derive(Debug)]
+struct Layout<'a, T: 'a> {
+    data: &'a [T],
+    min_indexes: &'a [usize],
+}
+
+// Derived `Copy` and `Clone` would require `T: Copy`, but we only hold references.
+impl<T> Clone for Layout<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Layout<'_, T> {}
+
 // Internal encapsulation of container + bounds
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug)]
 struct Range<'a, T: 'a> {
-    container: &'a RotatedArraySet<T>,
+    container: Layout<'a, T>,
     start_index_inclusive: usize,
     end_index_exclusive: usize,
 }
 
+// Derived `Copy` and `Clone` would require `T: Copy`, but we only hold a reference.
+impl<T> Clone for Range<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Range<'_, T> {}
+
 impl<'a, T> Range<'a, T>
 where
-    T: Ord + Copy + Default + Debug,
+    T: Ord,
 {
     fn with_bounds(
-        container: &'a RotatedArraySet<T>,
+        container: Layout<'a, T>,
         start_index_inclusive: usize,
         end_index_exclusive: usize,
     ) -> Range<'a, T> {
@@ -82,56 +130,221 @@ where
         }
     }
 
-    fn new(container: &'a RotatedArraySet<T>) -> Range<'a, T> {
+    fn new(container: Layout<'a, T>) -> Range<'a, T> {
         Range::with_bounds(container, 0, container.len())
     }
+}
+
+// An iterator over a range of a set that can skip ahead by exponential ("galloping") search
+// over ranks, using the constant-time `select`. Skipping `d` elements costs `O(lg d)`
+// comparisons, so merging a small set into a large one costs `O(m lg(n/m))` rather than
+// `O(m + n)`, while merging sets of similar size still costs `O(1)` per element.
+#[derive(Debug)]
+struct Galloping<'a, T: 'a> {
+    container: Layout<'a, T>,
+    end_index_exclusive: usize,
+    iter: Iter<'a, T>,
+}
+
+impl<T> Clone for Galloping<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Galloping<'_, T> {}
 
-    fn at(&self, index: usize) -> Option<&'a T> {
-        let container_idx = index + self.start_index_inclusive;
-        self.container.select(container_idx)
+impl<'a, T> Galloping<'a, T>
+where
+    T: Ord,
+{
+    fn new(range: Range<'a, T>) -> Galloping<'a, T> {
+        Galloping {
+            container: range.container,
+            end_index_exclusive: range.end_index_exclusive,
+            iter: Iter::new(range),
+        }
     }
 
     fn len(&self) -> usize {
-        self.end_index_exclusive - self.start_index_inclusive
+        self.iter.len()
+    }
+
+    fn peek(&self) -> Option<&'a T> {
+        self.iter.clone().next()
+    }
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.iter.next()
+    }
+
+    // Returns the number of remaining elements that are less than `value`.
+    fn count_less(&self, value: &T) -> usize {
+        let len = self.len();
+        let start = self.end_index_exclusive - len;
+        let is_less = |offset: usize| self.container.select(start + offset).unwrap() < value;
+        // gallop until we overshoot, maintaining that all elements before `lo` are less
+        let (mut lo, mut hi) = (0, 1);
+        while hi <= len && is_less(hi - 1) {
+            lo = hi;
+            hi *= 2;
+        }
+        // then binary search between the last two probes
+        let mut hi = min(hi - 1, len);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if is_less(mid) {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        lo
+    }
+
+    // Skips all remaining elements that are less than `value`.
+    fn skip_less(&mut self, value: &T) {
+        let count = self.count_less(value);
+        if count > 0 {
+            self.iter.nth(count - 1);
+        }
     }
 }
 
-/// An iterator over the items of a `RotatedArraySet`.
+/// An iterator over the items of a `RotatedArraySet`, `RotatedArrayMultiset` or `ArrayRotatedSet`.
 ///
-/// This `struct` is created by the [`iter`] method on [`RotatedArraySet`][`RotatedArraySet`].
-/// See its documentation for more.
+/// This `struct` is created by the [`iter`] method on [`RotatedArraySet`][`RotatedArraySet`],
+/// [`RotatedArrayMultiset`] and [`ArrayRotatedSet`], among others. See its documentation for more.
 ///
 /// [`RotatedArraySet`]: struct.RotatedArraySet.html
+/// [`RotatedArrayMultiset`]: struct.RotatedArrayMultiset.html
+/// [`ArrayRotatedSet`]: struct.ArrayRotatedSet.html
 /// [`iter`]: struct.RotatedArraySet.html#method.iter
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug)]
 pub struct Iter<'a, T: 'a> {
-    range: Range<'a, T>,
-    next_index: usize,
-    next_rev_index: usize,
+    // the chunks not yet visited from either end, between the partially visited chunks
+    chunks: Chunks<'a, T>,
+    front: &'a [T],
+    back: &'a [T],
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
 }
 
+impl<T> Copy for Iter<'_, T> {}
+
 impl<'a, T> Iter<'a, T>
 where
-    T: Ord + Copy + Default + Debug,
+    T: Ord,
 {
     fn new(range: Range<'a, T>) -> Iter<'a, T> {
-        let next_index = 0;
-        let next_rev_index = if range.len() == 0 { 0 } else { range.len() - 1 };
         Iter {
-            range,
-            next_index,
-            next_rev_index,
+            chunks: Chunks::new(range),
+            front: &[],
+            back: &[],
         }
     }
+}
 
-    #[inline(always)]
-    fn assert_invariants(&self) -> bool {
-        assert!(self.next_index <= self.range.len());
-        assert!(self.next_rev_index <= self.range.len());
-        if self.next_rev_index < self.next_index {
-            assert!(self.next_index - self.next_rev_index == 1);
+/// An iterator over the items of a `RotatedArraySet` or `RotatedArrayMultiset`,
+/// in sorted slices.
+///
+/// Each subarray of the 2-level rotated array consists of at most two sorted runs,
+/// on either side of its pivot, so iterating over the runs is much cheaper than iterating
+/// over individual elements. The slices are yielded in ascending order, so that
+/// concatenating them gives the same sequence as [`iter`].
+///
+/// This `struct` is created by the [`chunks`] method on [`RotatedArraySet`][`RotatedArraySet`],
+/// [`RotatedArrayMultiset`] and [`ArrayRotatedSet`]. See its documentation for more.
+///
+/// [`RotatedArraySet`]: struct.RotatedArraySet.html
+/// [`RotatedArrayMultiset`]: struct.RotatedArrayMultiset.html
+/// [`ArrayRotatedSet`]: struct.ArrayRotatedSet.html
+/// [`iter`]: struct.RotatedArraySet.html#method.iter
+/// [`chunks`]: struct.RotatedArraySet.html#method.chunks
+#[derive(Debug)]
+pub struct Chunks<'a, T: 'a> {
+    container: Layout<'a, T>,
+    // logical indexes bounding the elements not yet yielded from either end
+    front_index: usize,
+    back_index: usize,
+    // the subarrays containing `front_index` and `back_index - 1`, if the range is nonempty
+    front_subarray: SubarrayBounds,
+    back_subarray: SubarrayBounds,
+}
+
+// The index, logical start and length of a subarray, cached so that walking from one
+// subarray to the next never requires mapping an index back to a subarray from scratch.
+#[derive(Debug, Clone, Copy)]
+struct SubarrayBounds {
+    idx: usize,
+    start: usize,
+    len: usize,
+}
+
+impl<T> Clone for Chunks<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Chunks<'_, T> {}
+
+impl<'a, T> Chunks<'a, T>
+where
+    T: Ord,
+{
+    fn new(range: Range<'a, T>) -> Chunks<'a, T> {
+        let container = range.container;
+        let (front_index, back_index) = (range.start_index_inclusive, range.end_index_exclusive);
+        let (front_subarray, back_subarray) = if front_index < back_index {
+            (
+                container.subarray_bounds_of(front_index),
+                container.subarray_bounds_of(back_index - 1),
+            )
+        } else {
+            let empty = SubarrayBounds {
+                idx: 0,
+                start: 0,
+                len: 0,
+            };
+            (empty, empty)
+        };
+        Chunks {
+            container,
+            front_index,
+            back_index,
+            front_subarray,
+            back_subarray,
+        }
+    }
+
+    // the number of elements in the chunks not yet yielded
+    fn remaining(&self) -> usize {
+        self.back_index - self.front_index
+    }
+
+    // Skips the first `n` elements of the chunks not yet yielded (which must not exceed their number),
+    // so that the next chunk yielded from the front starts at the following element.
+    fn skip_front(&mut self, n: usize) {
+        debug_assert!(n <= self.remaining());
+        self.front_index += n;
+        if self.front_index < self.back_index {
+            self.front_subarray = self.container.subarray_bounds_of(self.front_index);
+        }
+    }
+
+    // Skips the last `n` elements of the chunks not yet yielded (which must not exceed their number),
+    // so that the next chunk yielded from the back ends at the preceding element.
+    fn skip_back(&mut self, n: usize) {
+        debug_assert!(n <= self.remaining());
+        self.back_index -= n;
+        if self.front_index < self.back_index {
+            self.back_subarray = self.container.subarray_bounds_of(self.back_index - 1);
         }
-        true
     }
 }
 
@@ -144,71 +357,125 @@ where
 /// [`into_iter`]: struct.RotatedArraySet.html#method.into_iter
 #[derive(Debug, Clone)]
 pub struct IntoIter<T> {
-    vec: Vec<T>,
-    next_index: usize,
+    iter: alloc::vec::IntoIter<T>,
 }
 
-/// A lazy iterator producing elements in the difference of `RotatedArraySet`s.
+/// A lazy iterator producing elements in the difference of `RotatedArraySet`s or `ArrayRotatedSet`s.
 ///
-/// This `struct` is created by the [`difference`] method on [`RotatedArraySet`].
-/// See its documentation for more.
+/// This `struct` is created by the [`difference`] method on [`RotatedArraySet`]
+/// and [`ArrayRotatedSet`]. See its documentation for more.
 ///
 /// [`RotatedArraySet`]: struct.RotatedArraySet.html
+/// [`ArrayRotatedSet`]: struct.ArrayRotatedSet.html
 /// [`difference`]: struct.RotatedArraySet.html#method.difference
 #[derive(Debug, Clone)]
 pub struct Difference<'a, T: 'a> {
-    self_iter: Iter<'a, T>,
-    other_set: &'a RotatedArraySet<T>,
+    a: Galloping<'a, T>,
+    b: Galloping<'a, T>,
+    // the number of values at the front of `a` known to be missing from `b`
+    a_run: usize,
 }
 
-/// A lazy iterator producing elements in the symmetric difference of `RotatedArraySet`s.
+/// A lazy iterator producing elements in the symmetric difference of `RotatedArraySet`s or `ArrayRotatedSet`s.
 ///
 /// This `struct` is created by the [`symmetric_difference`] method on
-/// [`RotatedArraySet`]. See its documentation for more.
+/// [`RotatedArraySet`] and [`ArrayRotatedSet`]. See its documentation for more.
 ///
 /// [`RotatedArraySet`]: struct.RotatedArraySet.html
+/// [`ArrayRotatedSet`]: struct.ArrayRotatedSet.html
 /// [`symmetric_difference`]: struct.RotatedArraySet.html#method.symmetric_difference
 #[derive(Debug, Clone)]
 pub struct SymmetricDifference<'a, T: 'a>
 where
-    T: Ord + Copy + Default + Debug,
+    T: Ord,
 {
-    a: Peekable<Iter<'a, T>>,
-    b: Peekable<Iter<'a, T>>,
+    a: Galloping<'a, T>,
+    b: Galloping<'a, T>,
+    // the number of values at the front of `a` (resp. `b`) known to be missing from `b` (resp. `a`)
+    a_run: usize,
+    b_run: usize,
 }
 
-/// A lazy iterator producing elements in the intersection of `RotatedArraySet`s.
+/// A lazy iterator producing elements in the intersection of `RotatedArraySet`s or `ArrayRotatedSet`s.
 ///
-/// This `struct` is created by the [`intersection`] method on [`RotatedArraySet`].
-/// See its documentation for more.
+/// This `struct` is created by the [`intersection`] method on [`RotatedArraySet`]
+/// and [`ArrayRotatedSet`]. See its documentation for more.
 ///
 /// [`RotatedArraySet`]: struct.RotatedArraySet.html
+/// [`ArrayRotatedSet`]: struct.ArrayRotatedSet.html
 /// [`intersection`]: struct.RotatedArraySet.html#method.intersection
 #[derive(Debug, Clone)]
 pub struct Intersection<'a, T: 'a> {
-    small_iter: Iter<'a, T>,
-    large_set: &'a RotatedArraySet<T>,
+    a: Galloping<'a, T>,
+    b: Galloping<'a, T>,
 }
 
-/// A lazy iterator producing elements in the union of `RotatedArraySet`s.
+/// A lazy iterator producing elements in the union of `RotatedArraySet`s or `ArrayRotatedSet`s.
 ///
-/// This `struct` is created by the [`union`] method on [`RotatedArraySet`].
-/// See its documentation for more.
+/// This `struct` is created by the [`union`] method on [`RotatedArraySet`]
+/// and [`ArrayRotatedSet`]. See its documentation for more.
 ///
 /// [`RotatedArraySet`]: struct.RotatedArraySet.html
+/// [`ArrayRotatedSet`]: struct.ArrayRotatedSet.html
 /// [`union`]: struct.RotatedArraySet.html#method.union
 #[derive(Debug, Clone)]
 pub struct Union<'a, T: 'a>
 where
-    T: Ord + Copy + Default + Debug,
+    T: Ord,
+{
+    a: Galloping<'a, T>,
+    b: Galloping<'a, T>,
+    // the number of values at the front of `a` (resp. `b`) known to be less than
+    // the next value of `b` (resp. `a`)
+    a_run: usize,
+    b_run: usize,
+}
+
+/// An iterator produced by calling `extract_if` on a `RotatedArraySet` or `RotatedArrayMultiset`.
+///
+/// This `struct` is created by the [`extract_if`] method on [`RotatedArraySet`]
+/// and [`RotatedArrayMultiset`]. See its documentation for more.
+///
+/// [`RotatedArraySet`]: struct.RotatedArraySet.html
+/// [`RotatedArrayMultiset`]: struct.RotatedArrayMultiset.html
+/// [`extract_if`]: struct.RotatedArraySet.html#method.extract_if
+pub struct ExtractIf<'a, T: 'a, F>
+where
+    T: Ord,
+    F: FnMut(&T) -> bool,
 {
-    a: Peekable<Iter<'a, T>>,
-    b: Peekable<Iter<'a, T>>,
+    set: &'a mut RotatedArraySet<T>,
+    // The set's data, taken for the lifetime of the iterator: unvisited elements
+    // are popped from the front, and retained elements are pushed onto the back.
+    deque: VecDeque<T>,
+    remaining: usize,
+    pred: F,
+}
+
+/// A draining iterator over a range of elements of a `RotatedArraySet` or `RotatedArrayMultiset`.
+///
+/// This `struct` is created by the [`drain`] and [`drain_ranks`] methods on
+/// [`RotatedArraySet`] and [`RotatedArrayMultiset`]. See their documentation for more.
+///
+/// If the iterator is leaked (e.g. with `mem::forget`), the set is left valid, but the
+/// values following the drained range may be lost as well.
+///
+/// [`RotatedArraySet`]: struct.RotatedArraySet.html
+/// [`RotatedArrayMultiset`]: struct.RotatedArrayMultiset.html
+/// [`drain`]: struct.RotatedArraySet.html#method.drain
+/// [`drain_ranks`]: struct.RotatedArraySet.html#method.drain_ranks
+#[derive(Debug)]
+pub struct Drain<'a, T: 'a> {
+    iter: alloc::vec::Drain<'a, T>,
+    // The set's auxiliary array, which is sized for the leading values that remain
+    // even if the iterator is leaked, and is resized for `subarray_count` on drop.
+    min_indexes: &'a mut Vec<usize>,
+    subarray_count: usize,
 }
 
 impl<T> RotatedArraySet<T>
 where
-    T: Ord + Copy + Default + Debug,
+    T: Ord,
 {
     /// Makes a new `RotatedArraySet` without any heap allocations.
     ///
@@ -226,7 +493,6 @@ where
         RotatedArraySet {
             data: Vec::new(),
             min_indexes: Vec::new(),
-            min_data: Vec::new(),
         }
     }
 
@@ -238,6 +504,10 @@ where
     /// It is important to note that although the returned set has the
     /// *capacity* specified, the set will have a zero *length*.
     ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -257,18 +527,181 @@ where
     /// set.insert(11);
     /// ```
     pub fn with_capacity(capacity: usize) -> RotatedArraySet<T> {
+        Self::check_capacity(capacity);
         let min_indexes_capacity = if capacity > 0 {
-            Self::get_subarray_idx_from_array_idx(capacity - 1) + 1
+            get_subarray_idx_from_array_idx(capacity - 1) + 1
         } else {
             0
         };
         RotatedArraySet {
             data: Vec::with_capacity(capacity),
             min_indexes: Vec::with_capacity(min_indexes_capacity),
-            min_data: Vec::with_capacity(min_indexes_capacity),
         }
     }
 
+    /// Tries to construct a new, empty `RotatedArraySet<T>` with the specified capacity,
+    /// like `with_capacity`, but returns an error rather than panicking or aborting if the
+    /// capacity can't be allocated in both the data array and the auxiliary array.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut set = RotatedArraySet::try_with_capacity(10).expect("out of memory");
+    ///
+    /// // These are all done without allocating
+    /// for i in 0..10 {
+    ///     set.insert(i);
+    /// }
+    ///
+    /// assert!(RotatedArraySet::<u32>::try_with_capacity(usize::MAX).is_err());
+    /// ```
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        if capacity > Self::MAX_LEN {
+            return Err(capacity_overflow());
+        }
+        let mut set = RotatedArraySet::new();
+        set.data.try_reserve_exact(capacity)?;
+        if capacity > 0 {
+            set.min_indexes
+                .try_reserve_exact(get_subarray_idx_from_array_idx(capacity - 1) + 1)?;
+        }
+        Ok(set)
+    }
+
+    /// Tries to reserve capacity for at least `additional` more values to be inserted
+    /// in the set, in both the data array and the auxiliary array.
+    ///
+    /// After a successful call, inserting up to `additional` values will not allocate.
+    /// If the capacity can't be reserved, an error is returned, and the set is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut set: RotatedArraySet<u32> = RotatedArraySet::new();
+    /// set.try_reserve(10).expect("out of memory");
+    ///
+    /// // These are all done without allocating
+    /// for i in 0..10 {
+    ///     set.insert(i);
+    /// }
+    ///
+    /// assert!(set.try_reserve(usize::MAX).is_err());
+    /// assert_eq!(set.len(), 10);
+    /// ```
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.data.try_reserve(additional)?;
+        // the reservation above would have failed if the new length overflowed
+        let new_len = self.data.len() + additional;
+        let subarray_count = if new_len == 0 {
+            0
+        } else {
+            get_subarray_idx_from_array_idx(new_len - 1) + 1
+        };
+        self.min_indexes
+            .try_reserve(subarray_count - self.min_indexes.len())
+    }
+
+    /// Builds a set from a vector, reusing its allocation, like `from`, but returns an error
+    /// rather than aborting if the auxiliary array can't be allocated. If an element occurs
+    /// more than once, the set keeps its first occurrence, just as with `from`.
+    ///
+    /// Unlike `from`, the vector is sorted in place by a stable sort that never allocates,
+    /// so this is an `O(n lg² n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set = RotatedArraySet::try_from_vec(vec![3, 1, 2, 1]).expect("out of memory");
+    /// assert!(set.iter().eq([1, 2, 3].iter()));
+    /// ```
+    pub fn try_from_vec(mut vec: Vec<T>) -> Result<Self, TryReserveError> {
+        // reserve the auxiliary array first, so that we don't sort in vain
+        let mut min_indexes = Vec::new();
+        if let Some(last_idx) = vec.len().checked_sub(1) {
+            min_indexes.try_reserve_exact(get_subarray_idx_from_array_idx(last_idx) + 1)?;
+        }
+        stable_sort_in_place(&mut vec);
+        vec.dedup();
+        let mut this = RotatedArraySet {
+            data: vec,
+            min_indexes,
+        };
+        this.init_min_indexes();
+        Ok(this)
+    }
+
+    /// Constructs a set from a vector whose elements are already sorted in
+    /// strictly ascending order (i.e., sorted and free of duplicates), without sorting it again.
+    ///
+    /// If the vector is not strictly ascending, it is returned unchanged as `Err`.
+    ///
+    /// This is an `O(n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set = RotatedArraySet::try_from_sorted(vec![1, 2, 3]).unwrap();
+    /// assert_eq!(set.len(), 3);
+    ///
+    /// assert_eq!(RotatedArraySet::try_from_sorted(vec![1, 1, 2]), Err(vec![1, 1, 2]));
+    /// assert_eq!(RotatedArraySet::try_from_sorted(vec![2, 1]), Err(vec![2, 1]));
+    /// ```
+    pub fn try_from_sorted(vec: Vec<T>) -> Result<Self, Vec<T>> {
+        if vec.windows(2).all(|w| w[0] < w[1]) {
+            Ok(Self::from_sorted_dedup_unchecked(vec))
+        } else {
+            Err(vec)
+        }
+    }
+
+    /// Constructs a set from a vector whose elements are already sorted in
+    /// strictly ascending order (i.e., sorted and free of duplicates), without checking
+    /// or sorting it.
+    ///
+    /// This is a constant-time operation (apart from allocating the `O(√n)` auxiliary array).
+    ///
+    /// It is a logic error for the vector not to be strictly ascending. The behavior
+    /// resulting from such a logic error is not specified (but will not be undefined
+    /// behavior), and is only detected in debug builds, where it causes a panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set = RotatedArraySet::from_sorted_dedup_unchecked(vec![1, 2, 3]);
+    /// assert_eq!(set.rank(&3), Ok(2));
+    /// ```
+    pub fn from_sorted_dedup_unchecked(vec: Vec<T>) -> Self {
+        debug_assert!(vec.windows(2).all(|w| w[0] < w[1]));
+        let mut this = RotatedArraySet {
+            data: vec,
+            min_indexes: Vec::new(),
+        };
+        this.init_min_indexes();
+        this
+    }
+
+    // Builds a set from an iterator over strictly ascending values in linear time,
+    // allocating the data array only once if the iterator's size hint has an upper bound.
+    fn from_sorted_dedup_iter<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = T>,
+    {
+        let (lower, upper) = iter.size_hint();
+        let mut vec = Vec::with_capacity(upper.unwrap_or(lower));
+        vec.extend(iter);
+        Self::from_sorted_dedup_unchecked(vec)
+    }
+
     /// Clears the set, removing all values.
     ///
     /// This is a constant-time operation.
@@ -286,11 +719,14 @@ where
     pub fn clear(&mut self) {
         self.data.clear();
         self.min_indexes.clear();
-        self.min_data.clear();
     }
 
     /// Returns `true` if the set contains a value.
     ///
+    /// The value may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
     /// This is an `O(lg n)` operation.
     ///
     /// # Examples
@@ -302,13 +738,21 @@ where
     /// assert_eq!(set.contains(&1), true);
     /// assert_eq!(set.contains(&4), false);
     /// ```
-    pub fn contains(&self, value: &T) -> bool {
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
         self.get(value).is_some()
     }
 
     /// Returns `true` if `self` has no elements in common with `other`.
     /// This is equivalent to checking for an empty intersection.
     ///
+    /// This takes `O(lg n)` time if the ranges spanned by the two sets don't overlap,
+    /// and `O(m lg(n/m + 1))` time otherwise, where `m` and `n` are the lengths
+    /// of the smaller and larger set respectively.
+    ///
     /// # Examples
     ///
     /// ```
@@ -324,12 +768,18 @@ where
     /// assert_eq!(a.is_disjoint(&b), false);
     /// ```
     pub fn is_disjoint(&self, other: &RotatedArraySet<T>) -> bool {
+        // `intersection` only visits the range where the two sets overlap,
+        // so this returns early if they don't overlap at all.
         self.intersection(other).next().is_none()
     }
 
     /// Returns `true` if the set is a subset of another,
     /// i.e., `other` contains at least all the values in `self`.
     ///
+    /// This takes constant time if `self` is larger than `other` or spans a range
+    /// not covered by `other`, and `O(m lg(n/m + 1))` time otherwise, where `m` and `n`
+    /// are the lengths of `self` and `other` respectively.
+    ///
     /// # Examples
     ///
     /// ```
@@ -345,24 +795,25 @@ where
     /// assert_eq!(set.is_subset(&sup), false);
     /// ```
     pub fn is_subset(&self, other: &RotatedArraySet<T>) -> bool {
-        // Same result as self.difference(other).next().is_none()
-        // but much faster.
         if self.len() > other.len() {
-            false
-        } else {
-            // Iterate `self`, searching for matches in `other`.
-            for next in self {
-                if !other.contains(next) {
-                    return false;
-                }
+            return false;
+        }
+        match (self.first(), self.last(), other.first(), other.last()) {
+            (None, ..) => true,
+            (Some(self_min), Some(self_max), Some(other_min), Some(other_max))
+                if self_min >= other_min && self_max <= other_max =>
+            {
+                self.difference(other).next().is_none()
             }
-            true
+            _ => false,
         }
     }
 
     /// Returns `true` if the set is a superset of another,
     /// i.e., `self` contains at least all the values in `other`.
     ///
+    /// This has the same complexity as [`is_subset`](#method.is_subset).
+    ///
     /// # Examples
     ///
     /// ```
@@ -386,6 +837,10 @@ where
 
     /// Returns a reference to the value in the set, if any, that is equal to the given value.
     ///
+    /// The value may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
     /// This is an `O(lg n)` operation.
     ///
     /// # Examples
@@ -397,8 +852,12 @@ where
     /// assert_eq!(set.get(&2), Some(&2));
     /// assert_eq!(set.get(&4), None);
     /// ```
-    pub fn get(&self, value: &T) -> Option<&T> {
-        let raw_idx = self.find_raw_index(value).ok()?;
+    pub fn get<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let raw_idx = self.layout().find_raw_index(value).ok()?;
         Some(&self.data[raw_idx])
     }
 
@@ -406,6 +865,10 @@ where
     /// or the rank of its largest predecessor plus one, if it does not exist (as `Result::Err`).
     /// This is a constant-time operation.
     ///
+    /// The value may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
     /// # Examples
     ///
     /// ```
@@ -415,33 +878,12 @@ where
     /// assert_eq!(set.rank(&1), Ok(0));
     /// assert_eq!(set.rank(&4), Err(3));
     /// ```
-    pub fn rank(&self, value: &T) -> Result<usize, usize> {
-        let (raw_index, exists) = match self.find_raw_index(value) {
-            Ok(index) => (index, true),
-            Err(index) => (index, false),
-        };
-        if raw_index == self.data.len() {
-            return Err(raw_index);
-        }
-        debug_assert!(raw_index < self.data.len());
-        let subarray_idx = Self::get_subarray_idx_from_array_idx(raw_index);
-        let subarray_start_idx = Self::get_array_idx_from_subarray_idx(subarray_idx);
-        let subarray_len = if subarray_idx == self.min_indexes.len() - 1 {
-            self.data.len() - subarray_start_idx
-        } else {
-            subarray_idx + 1
-        };
-        let pivot_idx = subarray_start_idx + self.min_indexes[subarray_idx];
-        let logical_index = if raw_index >= pivot_idx {
-            subarray_start_idx + raw_index - pivot_idx
-        } else {
-            subarray_start_idx + subarray_len - (pivot_idx - raw_index)
-        };
-        if exists {
-            Ok(logical_index)
-        } else {
-            Err(logical_index)
-        }
+    pub fn rank<Q>(&self, value: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.layout().rank_by(|x| x.borrow().cmp(value))
     }
 
     /// Returns a reference to the value in the set, if any, with the given rank.
@@ -458,288 +900,608 @@ where
     /// assert_eq!(set.select(3), None);
     /// ```
     pub fn select(&self, rank: usize) -> Option<&T> {
-        if rank >= self.data.len() {
-            return None;
-        }
-        let subarray_idx = Self::get_subarray_idx_from_array_idx(rank);
-        let subarray_start_idx = Self::get_array_idx_from_subarray_idx(subarray_idx);
-        let subarray_len = if subarray_idx == self.min_indexes.len() - 1 {
-            self.data.len() - subarray_start_idx
-        } else {
-            subarray_idx + 1
-        };
-        debug_assert!(rank >= subarray_start_idx);
-        let idx_offset = rank - subarray_start_idx;
-        let pivot_offset = self.min_indexes[subarray_idx];
-        let rotated_offset = (pivot_offset + idx_offset) % subarray_len;
-        debug_assert!(rotated_offset < subarray_len);
-        let raw_idx = subarray_start_idx + rotated_offset;
-        Some(&self.data[raw_idx])
+        self.layout().select(rank)
     }
 
-    /// Adds a value to the set.
+    /// Returns a reference to the value at the given quantile of the set, if any,
+    /// i.e., the value with rank `floor(q * (len - 1))`.
     ///
-    /// This is an `O(√n)` operation.
+    /// This is a constant-time operation.
     ///
-    /// If the set did not have this value present, `true` is returned.
+    /// # Panics
     ///
-    /// If the set did have this value present, `false` is returned, and the
-    /// entry is not updated.
+    /// Panics if `q` is not in the range `[0, 1]`.
     ///
     /// # Examples
     ///
     /// ```
     /// use rotated_array_set::RotatedArraySet;
     ///
-    /// let mut set = RotatedArraySet::new();
-    ///
-    /// assert_eq!(set.insert(2), true);
-    /// assert_eq!(set.insert(2), false);
-    /// assert_eq!(set.len(), 1);
+    /// let set: RotatedArraySet<_> = (1..=100).collect();
+    /// assert_eq!(set.quantile(0.0), Some(&1));
+    /// assert_eq!(set.quantile(0.9), Some(&90));
+    /// assert_eq!(set.quantile(1.0), Some(&100));
     /// ```
-    pub fn insert(&mut self, value: T) -> bool {
-        let insert_idx = match self.find_raw_index(&value).err() {
-            None => return false,
-            Some(idx) => idx,
-        };
-        // find subarray containing this insertion point
-        let subarray_idx = Self::get_subarray_idx_from_array_idx(insert_idx);
-        // inserted element could be in a new subarray
-        debug_assert!(subarray_idx <= self.min_indexes.len());
-        // create a new subarray if necessary
-        if subarray_idx == self.min_indexes.len() {
-            self.min_indexes.push(0);
-            self.min_data.push(T::default());
-        }
-        debug_assert_eq!(self.min_indexes.len(), self.min_data.len());
-        let subarray_offset = Self::get_array_idx_from_subarray_idx(subarray_idx);
-        // if insertion point is in last subarray and last subarray isn't full, just insert the new element
-        if subarray_idx == self.min_indexes.len() - 1 && !self.is_last_subarray_full() {
-            // Since we always insert into a partially full subarray in sorted order,
-            // there is no need to update the pivot location, but we do have to update
-            // the pivot value.
-            debug_assert!(self.min_indexes[subarray_idx] == 0);
-            self.data.insert(insert_idx, value);
-            // These writes are redundant unless the minimum has changed, but avoiding a branch may be worth it,
-            // given that the end of the data arrays should be in cache.
-            self.min_data[subarray_idx] = self.data[subarray_offset];
-            debug_assert!(self.assert_invariants());
-            return true;
-        }
-        // From now on, we can assume that the subarray we're inserting into is always full.
-        let next_subarray_offset = Self::get_array_idx_from_subarray_idx(subarray_idx + 1);
-        let subarray = &mut self.data[subarray_offset..next_subarray_offset];
-        let pivot_offset = self.min_indexes[subarray_idx];
-        let insert_offset = insert_idx - subarray_offset;
-        let max_offset = if pivot_offset == 0 {
-            subarray.len() - 1
-        } else {
-            pivot_offset - 1
-        };
-        let mut prev_max = subarray[max_offset];
-        // this logic is best understood with a diagram of a rotated array, e.g.:
-        //
-        // ------------------------------------------------------------------------
-        // | 12 | 13 | 14 | 15 | 16 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9 | 10 | 11 |
-        // ------------------------------------------------------------------------
-        //
-        if max_offset < pivot_offset && insert_offset >= pivot_offset {
-            subarray.copy_within(pivot_offset..insert_offset, max_offset);
-            subarray[insert_offset - 1] = value;
-            self.min_indexes[subarray_idx] = max_offset;
-            self.min_data[subarray_idx] = subarray[max_offset];
-        } else {
-            subarray.copy_within(insert_offset..max_offset, insert_offset + 1);
-            subarray[insert_offset] = value;
-            if insert_offset == pivot_offset {
-                // inserted value is new minimum for subarray
-                self.min_data[subarray_idx] = value;
-            }
-        }
-        debug_assert!(self.assert_invariants());
-        let max_subarray_idx = self.min_indexes.len() - 1;
-        let next_subarray_idx = subarray_idx + 1;
-        let last_subarray_full = self.is_last_subarray_full();
-        // now loop over all remaining subarrays, setting the min (pivot) of each to the max of its predecessor
-        for (i, pivot_offset_ref) in self.min_indexes[next_subarray_idx..].iter_mut().enumerate() {
-            let cur_subarray_idx = next_subarray_idx + i;
-            // if the last subarray isn't full, skip it
-            if cur_subarray_idx == max_subarray_idx && !last_subarray_full {
-                break;
-            }
-            let max_offset = if *pivot_offset_ref == 0 {
-                cur_subarray_idx
-            } else {
-                *pivot_offset_ref - 1
-            };
-            let max_idx = max_offset + Self::get_array_idx_from_subarray_idx(cur_subarray_idx);
-            let next_max = self.data[max_idx];
-            self.data[max_idx] = prev_max;
-            *pivot_offset_ref = max_offset;
-            self.min_data[cur_subarray_idx] = prev_max;
-            prev_max = next_max;
-        }
-        // if the last subarray was full, append current max to a new subarray, otherwise insert max in sorted order
-        if last_subarray_full {
-            self.data.push(prev_max);
-            self.min_indexes.push(0);
-            self.min_data.push(prev_max);
-        } else {
-            let max_subarray_offset = Self::get_array_idx_from_subarray_idx(max_subarray_idx);
-            // since `max` is guaranteed to be <= the pivot value, we always insert it at the pivot location
-            debug_assert!(prev_max <= self.data[max_subarray_offset]);
-            self.data.insert(max_subarray_offset, prev_max);
-            self.min_data[max_subarray_idx] = prev_max;
-        }
-        debug_assert!(self.find_raw_index(&value).is_ok());
-        debug_assert!(self.assert_invariants());
-        true
+    pub fn quantile(&self, q: f64) -> Option<&T> {
+        self.select(quantile_rank(q, self.len())?)
     }
 
-    /// Removes a value from the set. Returns whether the value was
-    /// present in the set.
+    /// Returns a reference to the median value of the set, if any. If the set has
+    /// an even number of values, this is the lower of the two middle values.
     ///
-    /// This is an `O(√n)` operation.
+    /// This is a constant-time operation.
     ///
     /// # Examples
     ///
     /// ```
     /// use rotated_array_set::RotatedArraySet;
     ///
-    /// let mut set = RotatedArraySet::new();
-    ///
-    /// set.insert(2);
-    /// assert_eq!(set.remove(&2), true);
-    /// assert_eq!(set.remove(&2), false);
+    /// let mut set: RotatedArraySet<_> = vec![1, 2, 3].into();
+    /// assert_eq!(set.median(), Some(&2));
+    /// set.insert(4);
+    /// assert_eq!(set.median(), Some(&2));
     /// ```
-    pub fn remove(&mut self, value: &T) -> bool {
-        let mut remove_idx = match self.find_raw_index(&value).ok() {
-            Some(idx) => idx,
-            None => return false,
-        };
-        let max_subarray_idx = self.min_indexes.len() - 1;
-        let max_subarray_offset = Self::get_array_idx_from_subarray_idx(max_subarray_idx);
-        // find subarray containing the element to remove
-        let subarray_idx = Self::get_subarray_idx_from_array_idx(remove_idx);
-        debug_assert!(subarray_idx <= max_subarray_idx);
-        let subarray_offset = Self::get_array_idx_from_subarray_idx(subarray_idx);
-        // if we're not removing an element in the last subarray, then we end up deleting its minimum,
-        // which is always at the first offset since it's sorted
-        let mut max_subarray_remove_idx = if subarray_idx == max_subarray_idx {
-            remove_idx
+    pub fn median(&self) -> Option<&T> {
+        self.quantile(0.5)
+    }
+
+    /// Returns the percentile rank of the given value in the set, i.e., the percentage
+    /// of values in the set that are less than the given value, counting a value equal to it
+    /// as half. Returns 0 if the set is empty.
+    ///
+    /// The value may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set: RotatedArraySet<_> = vec![1, 2, 3, 4].into();
+    /// assert_eq!(set.percentile_rank(&0), 0.0);
+    /// assert_eq!(set.percentile_rank(&2), 37.5);
+    /// assert_eq!(set.percentile_rank(&5), 100.0);
+    /// ```
+    pub fn percentile_rank<Q>(&self, value: &Q) -> f64
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.rank(value) {
+            Ok(rank) => percentile_rank(rank, 1, self.len()),
+            Err(rank) => percentile_rank(rank, 0, self.len()),
+        }
+    }
+
+    /// Returns the number of values in the given range, which may be specified
+    /// as for [`range`].
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set: RotatedArraySet<_> = vec![3, 5, 8].into();
+    /// assert_eq!(set.count_range(4..), 2);
+    /// assert_eq!(set.count_range(4..8), 1);
+    /// ```
+    ///
+    /// [`range`]: #method.range
+    pub fn count_range<K, R>(&self, range: R) -> usize
+    where
+        T: Borrow<K>,
+        K: Ord + ?Sized,
+        R: RangeBounds<K>,
+    {
+        let range = self.layout().get_range(range);
+        range.end_index_exclusive - range.start_index_inclusive
+    }
+
+    /// Returns a reference to the value with the given rank within the given range,
+    /// which may be specified as for [`range`], if any.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set: RotatedArraySet<_> = vec![3, 5, 8, 13].into();
+    /// assert_eq!(set.nth_in_range(4.., 1), Some(&8));
+    /// assert_eq!(set.nth_in_range(4..8, 1), None);
+    /// ```
+    ///
+    /// [`range`]: #method.range
+    pub fn nth_in_range<K, R>(&self, range: R, n: usize) -> Option<&T>
+    where
+        T: Borrow<K>,
+        K: Ord + ?Sized,
+        R: RangeBounds<K>,
+    {
+        let range = self.layout().get_range(range);
+        if n < range.end_index_exclusive - range.start_index_inclusive {
+            self.select(range.start_index_inclusive + n)
         } else {
-            max_subarray_offset
-        };
-        // if the last subarray was rotated, sort it to maintain insert invariant
-        if self.is_last_subarray_full() {
-            let last_min_offset = self.min_indexes[max_subarray_idx];
-            // rotate left by the min offset instead of sorting
-            self.data[max_subarray_offset..].rotate_left(last_min_offset);
-            self.min_indexes[max_subarray_idx] = 0;
-            // the remove index might change after sorting the last subarray
-            if subarray_idx == max_subarray_idx {
-                remove_idx = self
-                    .find_raw_index(&value)
-                    .expect("recalculating remove index after sorting");
-                max_subarray_remove_idx = remove_idx;
-            }
+            None
         }
-        // if insertion point is not in last subarray, perform a "hard exchange"
-        if subarray_idx < max_subarray_idx {
-            // From now on, we can assume that the subarray we're removing from is full.
-            let next_subarray_offset = Self::get_array_idx_from_subarray_idx(subarray_idx + 1);
-            let subarray = &mut self.data[subarray_offset..next_subarray_offset];
-            let pivot_offset = self.min_indexes[subarray_idx];
-            let remove_offset = remove_idx - subarray_offset;
-            let max_offset = if pivot_offset == 0 {
-                subarray.len() - 1
-            } else {
-                pivot_offset - 1
-            };
-            // this logic is best understood with a diagram of a rotated array, e.g.:
-            //
-            // ------------------------------------------------------------------------
-            // | 12 | 13 | 14 | 15 | 16 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9 | 10 | 11 |
-            // ------------------------------------------------------------------------
-            //
-            let mut prev_max_offset = if max_offset < pivot_offset && remove_offset >= pivot_offset
-            {
-                subarray.copy_within(pivot_offset..remove_offset, pivot_offset + 1);
-                let new_pivot_offset = if pivot_offset == subarray.len() - 1 {
-                    0
+    }
+
+    /// Returns a reference to the greatest value in the set that is less than or equal to
+    /// the given value, if any.
+    ///
+    /// The value may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set: RotatedArraySet<_> = vec![1, 3, 5].into();
+    /// assert_eq!(set.floor(&0), None);
+    /// assert_eq!(set.floor(&3), Some(&3));
+    /// assert_eq!(set.floor(&4), Some(&3));
+    /// ```
+    pub fn floor<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.layout().find_raw_index(value) {
+            Ok(raw_idx) => Some(&self.data[raw_idx]),
+            Err(insert_idx) => self.select_before_raw_idx(insert_idx),
+        }
+    }
+
+    /// Returns a reference to the least value in the set that is greater than or equal to
+    /// the given value, if any.
+    ///
+    /// The value may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set: RotatedArraySet<_> = vec![1, 3, 5].into();
+    /// assert_eq!(set.ceiling(&2), Some(&3));
+    /// assert_eq!(set.ceiling(&3), Some(&3));
+    /// assert_eq!(set.ceiling(&6), None);
+    /// ```
+    pub fn ceiling<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.layout().find_raw_index(value) {
+            Ok(raw_idx) => Some(&self.data[raw_idx]),
+            Err(insert_idx) => self.data.get(insert_idx),
+        }
+    }
+
+    /// Returns a reference to the greatest value in the set that is strictly less than
+    /// the given value, if any.
+    ///
+    /// The value may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set: RotatedArraySet<_> = vec![1, 3, 5].into();
+    /// assert_eq!(set.strictly_below(&1), None);
+    /// assert_eq!(set.strictly_below(&3), Some(&1));
+    /// assert_eq!(set.strictly_below(&4), Some(&3));
+    /// ```
+    pub fn strictly_below<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.layout().find_raw_index(value) {
+            Ok(raw_idx) | Err(raw_idx) => self.select_before_raw_idx(raw_idx),
+        }
+    }
+
+    /// Returns a reference to the least value in the set that is strictly greater than
+    /// the given value, if any.
+    ///
+    /// The value may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set: RotatedArraySet<_> = vec![1, 3, 5].into();
+    /// assert_eq!(set.strictly_above(&2), Some(&3));
+    /// assert_eq!(set.strictly_above(&3), Some(&5));
+    /// assert_eq!(set.strictly_above(&5), None);
+    /// ```
+    pub fn strictly_above<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.layout().find_raw_index(value) {
+            Ok(raw_idx) => self.select(self.layout().get_logical_idx_from_raw_idx(raw_idx) + 1),
+            Err(insert_idx) => self.data.get(insert_idx),
+        }
+    }
+
+    /// Returns a reference to the value in the set nearest to the given value, if any,
+    /// according to the given distance function. If the set contains the given value,
+    /// that value is returned; otherwise the distances from the given value to its nearest
+    /// neighbors below and above are compared, and the neighbor below wins ties.
+    ///
+    /// The value may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set: RotatedArraySet<i32> = vec![1, 4, 9].into();
+    /// let distance = |x: &i32, y: &i32| (x - y).abs();
+    /// assert_eq!(set.nearest(&2, distance), Some(&1));
+    /// assert_eq!(set.nearest(&3, distance), Some(&4));
+    /// assert_eq!(set.nearest(&100, distance), Some(&9));
+    /// ```
+    pub fn nearest<Q, D, F>(&self, value: &Q, mut distance: F) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        D: PartialOrd,
+        F: FnMut(&Q, &T) -> D,
+    {
+        let insert_idx = match self.layout().find_raw_index(value) {
+            Ok(raw_idx) => return Some(&self.data[raw_idx]),
+            Err(insert_idx) => insert_idx,
+        };
+        match (
+            self.select_before_raw_idx(insert_idx),
+            self.data.get(insert_idx),
+        ) {
+            (Some(below), Some(above)) => {
+                if distance(value, above) < distance(value, below) {
+                    Some(above)
                 } else {
-                    pivot_offset + 1
-                };
-                self.min_indexes[subarray_idx] = new_pivot_offset;
-                self.min_data[subarray_idx] = subarray[new_pivot_offset];
-                pivot_offset
-            } else {
-                subarray.copy_within(remove_offset + 1..=max_offset, remove_offset);
-                if remove_offset == pivot_offset {
-                    self.min_data[subarray_idx] = subarray[pivot_offset];
+                    Some(below)
                 }
-                max_offset
-            };
-            let next_subarray_idx = min(max_subarray_idx, subarray_idx + 1);
-            // now perform an "easy exchange" in all remaining subarrays except the last,
-            // setting the max of each to the min of its successor.
-            for (i, pivot_offset_ref) in self.min_indexes[next_subarray_idx..max_subarray_idx]
-                .iter_mut()
-                .enumerate()
-            {
-                let cur_subarray_idx = next_subarray_idx + i;
-                let cur_subarray_offset = Self::get_array_idx_from_subarray_idx(cur_subarray_idx);
-                let prev_max_idx =
-                    prev_max_offset + Self::get_array_idx_from_subarray_idx(cur_subarray_idx - 1);
-                self.data[prev_max_idx] = self.data[cur_subarray_offset + *pivot_offset_ref];
-                // the min_data array needs to be updated when the previous subarray's max offset
-                // coincides with its min offset, i.e., when it is subarray 0
-                if cur_subarray_idx == 1 {
-                    self.min_data[0] = self.data[0];
-                    debug_assert!(IsSorted::is_sorted(&mut self.min_data.iter()));
-                }
-                prev_max_offset = *pivot_offset_ref;
-                let new_min_offset = if *pivot_offset_ref == cur_subarray_idx {
-                    0
-                } else {
-                    *pivot_offset_ref + 1
-                };
-                *pivot_offset_ref = new_min_offset;
-                self.min_data[cur_subarray_idx] = self.data[cur_subarray_offset + new_min_offset];
-                debug_assert!(IsSorted::is_sorted(&mut self.min_data.iter()));
             }
-            // now we fix up the last subarray. if it was initially full, we need to sort it to maintain the insert invariant.
-            // if the removed element is in the last subarray, we just sort and remove() on the vec, updating auxiliary arrays.
-            // otherwise, we copy the minimum to the max position of the previous subarray, then remove it and fix up
-            // auxiliary arrays.
-            let prev_max_idx =
-                prev_max_offset + Self::get_array_idx_from_subarray_idx(max_subarray_idx - 1);
-            // since the last subarray is always sorted, its minimum element is always on the first offset
-            self.data[prev_max_idx] = self.data[max_subarray_offset];
-            // the min_data array needs to be updated when the previous subarray's max offset
-            // coincides with its min offset, i.e., when it is subarray 0
-            if max_subarray_idx == 1 {
-                self.min_data[0] = self.data[0];
-                debug_assert!(IsSorted::is_sorted(&mut self.min_data.iter()));
+            (below, above) => below.or(above),
+        }
+    }
+
+    // Returns the value preceding the given raw index (or insertion point), using the
+    // constant-time translation to a logical index rather than another search.
+    fn select_before_raw_idx(&self, raw_idx: usize) -> Option<&T> {
+        let idx = self.layout().get_logical_idx_from_raw_idx(raw_idx);
+        self.select(idx.checked_sub(1)?)
+    }
+
+    /// Returns a reference to the first (i.e., smallest) value in the set, if any.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut set = RotatedArraySet::new();
+    /// assert_eq!(set.first(), None);
+    /// set.insert(2);
+    /// set.insert(1);
+    /// assert_eq!(set.first(), Some(&1));
+    /// ```
+    pub fn first(&self) -> Option<&T> {
+        // the first subarray has a single element
+        self.data.first()
+    }
+
+    /// Returns a reference to the last (i.e., largest) value in the set, if any.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut set = RotatedArraySet::new();
+    /// assert_eq!(set.last(), None);
+    /// set.insert(1);
+    /// set.insert(2);
+    /// assert_eq!(set.last(), Some(&2));
+    /// ```
+    pub fn last(&self) -> Option<&T> {
+        self.select(self.data.len().checked_sub(1)?)
+    }
+
+    /// Removes and returns the first (i.e., smallest) value in the set, if any.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut set: RotatedArraySet<_> = vec![1, 2].into();
+    /// assert_eq!(set.pop_first(), Some(1));
+    /// assert_eq!(set.pop_first(), Some(2));
+    /// assert_eq!(set.pop_first(), None);
+    /// ```
+    pub fn pop_first(&mut self) -> Option<T> {
+        self.remove_at(0)
+    }
+
+    /// Removes and returns the last (i.e., largest) value in the set, if any.
+    ///
+    /// The last subarray is kept sorted unless it is full, so this is an `O(1)` operation,
+    /// except when the last subarray is full, in which case it is `O(√n)`. (In particular,
+    /// all but the first of a sequence of `pop_last` calls take constant time.)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut set: RotatedArraySet<_> = vec![1, 2].into();
+    /// assert_eq!(set.pop_last(), Some(2));
+    /// assert_eq!(set.pop_last(), Some(1));
+    /// assert_eq!(set.pop_last(), None);
+    /// ```
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.remove_at(self.data.len().checked_sub(1)?)
+    }
+
+    /// Adds a value to the set.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// If the set did not have this value present, `true` is returned.
+    ///
+    /// If the set did have this value present, `false` is returned, and the
+    /// entry is not updated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut set = RotatedArraySet::new();
+    ///
+    /// assert_eq!(set.insert(2), true);
+    /// assert_eq!(set.insert(2), false);
+    /// assert_eq!(set.len(), 1);
+    /// ```
+    pub fn insert(&mut self, value: T) -> bool {
+        match self.layout().find_raw_index(&value) {
+            Ok(_) => false,
+            Err(insert_idx) => {
+                self.insert_raw(insert_idx, value);
+                true
+            }
+        }
+    }
+
+    /// Adds a value to the set, like `insert`, but returns an error rather than aborting
+    /// if the set needs to grow and the allocation fails.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// Any allocation is made before values are shifted between subarrays, so if it fails,
+    /// the value is dropped, and the set is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut set = RotatedArraySet::new();
+    ///
+    /// assert_eq!(set.try_insert(2), Ok(true));
+    /// assert_eq!(set.try_insert(2), Ok(false));
+    /// assert_eq!(set.len(), 1);
+    /// ```
+    pub fn try_insert(&mut self, value: T) -> Result<bool, TryReserveError> {
+        match self.layout().find_raw_index(&value) {
+            Ok(_) => Ok(false),
+            Err(insert_idx) => {
+                self.try_reserve(1)?;
+                self.insert_raw(insert_idx, value);
+                Ok(true)
             }
         }
-        self.data.remove(max_subarray_remove_idx);
-        // if last subarray is now empty, trim the auxiliary arrays
-        if max_subarray_offset == self.data.len() {
-            self.min_indexes.pop();
-            self.min_data.pop();
+    }
+
+    // Inserts a value at the given raw insertion point.
+    fn insert_raw(&mut self, insert_idx: usize, value: T) {
+        Self::check_capacity(self.data.len() + 1);
+        insert_raw(&mut self.data, &mut self.min_indexes, insert_idx, value);
+    }
+
+    /// Adds all values from an iterator that yields them in ascending order, merging
+    /// them into the set. Values already present in the set are not replaced, and only the
+    /// first of several equal values from the iterator is added, just as if the values had
+    /// been inserted in order.
+    ///
+    /// This takes `O(k√n)` time for a small number `k` of values, and `O(n + k)` time otherwise,
+    /// since the values don't need to be sorted before merging. (Unsorted batches can be added
+    /// with `extend` instead.) The values are merged in place, without any storage beyond
+    /// the capacity needed to hold them.
+    ///
+    /// It is a logic error for the iterator not to yield its values in ascending order
+    /// (though it may yield equal values consecutively). The behavior resulting from such a
+    /// logic error is not specified (but will not be undefined behavior), and is only
+    /// detected in debug builds, where it causes a panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut set: RotatedArraySet<_> = vec![1, 5, 9].into();
+    /// set.insert_sorted_batch(vec![2, 3, 5, 5, 8]);
+    /// assert!(set.iter().eq([1, 2, 3, 5, 8, 9].iter()));
+    /// ```
+    pub fn insert_sorted_batch<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let batch: Vec<T> = iter.into_iter().collect();
+        debug_assert!(IsSorted::is_sorted(&mut batch.iter()));
+        self.insert_batch(batch);
+    }
+
+    // Inserts a sorted batch of values, either one at a time or by merging and rebuilding,
+    // depending on which is cheaper.
+    fn insert_batch(&mut self, mut batch: Vec<T>) {
+        if self.prefers_bulk_insert(batch.len()) {
+            self.merge_batch(batch, true);
         } else {
-            // since the last subarray is always sorted, its minimum is always on the first offset
-            self.min_data[max_subarray_idx] = self.data[max_subarray_offset];
-            debug_assert!(IsSorted::is_sorted(&mut self.min_data.iter()));
+            for value in batch.drain(..) {
+                self.insert(value);
+            }
+        }
+    }
+
+    // Returns whether merging a batch of the given size is cheaper than inserting its values
+    // one at a time, each of which costs `O(√n)`.
+    fn prefers_bulk_insert(&self, batch_len: usize) -> bool {
+        batch_len.saturating_mul(self.min_indexes.len() + 1) > self.len()
+    }
+
+    // Merges a sorted batch of values into the data array and rebuilds the auxiliary array.
+    // On ties, values already in the array precede values from the batch, and if `dedup` is
+    // true, only the first of several equal values is kept.
+    fn merge_batch(&mut self, batch: Vec<T>, dedup: bool) {
+        debug_assert!(IsSorted::is_sorted(&mut batch.iter()));
+        let additional = batch.len();
+        self.merge_sorted(batch.into_iter(), additional, dedup);
+    }
+
+    // Merges values yielded in ascending order into the data array in place, and rebuilds
+    // the auxiliary array, with the same tie-breaking as `merge_batch`. If a comparison
+    // panics, a valid (though possibly empty) set is left behind.
+    fn merge_sorted<I>(&mut self, values: I, additional: usize, dedup: bool)
+    where
+        I: Iterator<Item = T>,
+    {
+        // rotate all subarrays into sorted order
+        self.sort_subarrays(0, self.min_indexes.len());
+        self.min_indexes.clear();
+        // Merge through a ring buffer over our own array: our unmerged values stay at the front,
+        // and the merged values are pushed to the back as our values are popped from the front,
+        // so the merged values never overwrite an unmerged value.
+        let mut deque = VecDeque::from(mem::take(&mut self.data));
+        deque.reserve(additional);
+        let mut remaining = deque.len();
+        for value in values {
+            while remaining > 0 {
+                match deque[0].cmp(&value) {
+                    Less => {}
+                    Equal if !dedup => {}
+                    _ => break,
+                }
+                let smaller = deque.pop_front().unwrap();
+                deque.push_back(smaller);
+                remaining -= 1;
+            }
+            // a duplicate equals either the next unmerged value or the last merged value
+            let is_duplicate = dedup
+                && ((remaining > 0 && deque[0] == value)
+                    || (deque.len() > remaining && *deque.back().unwrap() == value));
+            if !is_duplicate {
+                deque.push_back(value);
+            }
+        }
+        // the unmerged values are at the front, and are all greater than the merged values
+        deque.rotate_left(remaining);
+        self.data = Vec::from(deque);
+        self.init_min_indexes();
+        debug_assert!(self.layout().assert_invariants());
+    }
+
+    /// Removes a value from the set. Returns whether the value was
+    /// present in the set.
+    ///
+    /// The value may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut set = RotatedArraySet::new();
+    ///
+    /// set.insert(2);
+    /// assert_eq!(set.remove(&2), true);
+    /// assert_eq!(set.remove(&2), false);
+    /// ```
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.layout().find_raw_index(value) {
+            Ok(idx) => {
+                self.remove_raw(idx);
+                debug_assert!(self.layout().find_raw_index(value).is_err());
+                true
+            }
+            Err(_) => false,
         }
-        debug_assert!(self.find_raw_index(&value).is_err());
-        debug_assert!(self.assert_invariants());
-        true
     }
 
     /// Removes and returns the value in the set, if any, that is equal to the given one.
     ///
+    /// The value may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
     /// This is an `O(√n)` operation.
     ///
     /// # Examples
@@ -751,16 +1513,47 @@ where
     /// assert_eq!(set.take(&2), Some(2));
     /// assert_eq!(set.take(&2), None);
     /// ```
-    pub fn take(&mut self, value: &T) -> Option<T> {
-        let ret = self.get(value).copied();
-        if ret.is_some() {
-            self.remove(value);
+    pub fn take<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let raw_idx = self.layout().find_raw_index(value).ok()?;
+        Some(self.remove_raw(raw_idx))
+    }
+
+    /// Removes and returns the value in the set, if any, with the given rank.
+    ///
+    /// Unlike `take`, this doesn't search for the value, so repeatedly calling
+    /// `remove_at(0)` is an efficient way to evict the smallest values.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut set: RotatedArraySet<_> = vec![1, 2, 3].into();
+    /// assert_eq!(set.remove_at(1), Some(2));
+    /// assert_eq!(set.remove_at(2), None);
+    /// assert_eq!(set.remove_at(0), Some(1));
+    /// ```
+    pub fn remove_at(&mut self, rank: usize) -> Option<T> {
+        if rank >= self.data.len() {
+            return None;
         }
-        ret
+        let raw_idx = self.layout().get_raw_idx_from_logical_idx(rank);
+        Some(self.remove_raw(raw_idx))
     }
 
     /// Moves all elements from `other` into `Self`, leaving `other` empty.
     ///
+    /// The two sets are merged in place, in linear time, without any storage beyond
+    /// the capacity needed by `self` to hold the merged values. If both sets contain
+    /// equal values, the value in `self` is kept. If comparing two values panics, both
+    /// sets are left valid, but may have lost some or all of their values.
+    ///
     /// # Examples
     ///
     /// ```
@@ -788,17 +1581,29 @@ where
     /// assert!(a.contains(&5));
     /// ```
     pub fn append(&mut self, other: &mut Self) {
-        // allocate new set and copy union into it
-        let mut union: Self = self.union(other).cloned().collect();
-        // empty `other`
-        other.clear();
-        // steal data from new set and drop data from old set
-        mem::swap(self, &mut union);
+        if other.is_empty() {
+            return;
+        }
+        if self.is_empty() {
+            mem::swap(self, other);
+            return;
+        }
+        other.sort_subarrays(0, other.min_indexes.len());
+        other.min_indexes.clear();
+        let additional = other.data.len();
+        self.merge_sorted(other.data.drain(..), additional, true);
     }
 
     /// Splits the collection into two at `value`. Returns everything after `value`,
     /// including `value` itself.
     ///
+    /// The values after `value` are moved directly into the returned set, which takes
+    /// time linear in their number.
+    ///
+    /// The value may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
     /// # Examples
     ///
     /// Basic usage:
@@ -825,23 +1630,131 @@ where
     /// assert!(b.contains(&17));
     /// assert!(b.contains(&41));
     /// ```
-    pub fn split_off(&mut self, value: &T) -> Self {
-        let tail = self.range((Included(value), Unbounded));
-        if tail.len() == 0 {
+    pub fn split_off<Q>(&mut self, value: &Q) -> Self
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let tail_start = match self.rank(value) {
+            Ok(rank) => rank,
+            Err(rank) => rank,
+        };
+        self.split_off_at(tail_start)
+    }
+
+    /// Splits the collection into two at the given rank. Returns a set containing
+    /// the values with ranks in `[rank, len)`, leaving the values with ranks in `[0, rank)`
+    /// in `self`.
+    ///
+    /// The values from `rank` onward are moved directly into the returned set, which takes
+    /// time linear in their number.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rank > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut a: RotatedArraySet<_> = vec![1, 2, 3, 17, 41].into();
+    /// let b = a.split_off_at(2);
+    ///
+    /// assert_eq!(a.iter().collect::<Vec<_>>(), [&1, &2]);
+    /// assert_eq!(b.iter().collect::<Vec<_>>(), [&3, &17, &41]);
+    /// ```
+    pub fn split_off_at(&mut self, rank: usize) -> Self {
+        assert!(rank <= self.len(), "`rank` out of bounds");
+        let tail_start = rank;
+        if tail_start == self.len() {
             // if key follows everything in set, just return empty set
             Self::default()
-        } else if tail.len() == self.len() {
+        } else if tail_start == 0 {
             // if key precedes everything in set, just return moved self
-            mem::replace(self, Self::default())
+            mem::take(self)
         } else {
-            // return tail and truncate self
-            let new_len = self.len() - tail.len();
-            let tail_set: Self = tail.cloned().collect();
-            self.truncate(new_len);
+            // sort all subarrays overlapping the tail, so we can move the tail out directly
+            let subarray_idx = get_subarray_idx_from_array_idx(tail_start);
+            self.sort_subarrays(subarray_idx, self.min_indexes.len());
+            // the tail is already sorted and free of duplicates, so it needs no sorting
+            let tail_set = Self::from_sorted_dedup_unchecked(self.data.split_off(tail_start));
+            // trim auxiliary array (the subarray containing the split point, if any, is already sorted)
+            let last_subarray_idx = get_subarray_idx_from_array_idx(tail_start - 1);
+            self.min_indexes.truncate(last_subarray_idx + 1);
+            debug_assert!(self.layout().assert_invariants());
             tail_set
         }
     }
 
+    /// Returns iterators over the values with ranks in `[0, rank)` and `[rank, len)`
+    /// respectively, in ascending order.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rank > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set: RotatedArraySet<_> = vec![1, 2, 3, 17, 41].into();
+    /// let (head, tail) = set.split_at_rank(2);
+    ///
+    /// assert_eq!(head.collect::<Vec<_>>(), [&1, &2]);
+    /// assert_eq!(tail.collect::<Vec<_>>(), [&3, &17, &41]);
+    /// ```
+    pub fn split_at_rank(&self, rank: usize) -> (Iter<'_, T>, Iter<'_, T>) {
+        assert!(rank <= self.len(), "`rank` out of bounds");
+        (
+            Iter::new(Range::with_bounds(self.layout(), 0, rank)),
+            Iter::new(Range::with_bounds(self.layout(), rank, self.len())),
+        )
+    }
+
+    /// Concatenates two sets, where every value of `other` is greater than every
+    /// value of `self`, without comparing any values.
+    ///
+    /// Appending values after the end of the array doesn't move any existing values
+    /// between subarrays, so only the subarrays receiving values from `other` need to be
+    /// laid out again. This takes time linear in the length of `other`.
+    ///
+    /// It is a logic error for any value of `other` to be less than or equal to any value
+    /// of `self`. The behavior resulting from such a logic error is not specified (but will
+    /// not be undefined behavior), and is only detected in debug builds, where it causes
+    /// a panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let a: RotatedArraySet<_> = vec![1, 2, 3].into();
+    /// let b: RotatedArraySet<_> = vec![17, 41].into();
+    /// let set = a.concat_ordered(b);
+    ///
+    /// assert_eq!(set.iter().collect::<Vec<_>>(), [&1, &2, &3, &17, &41]);
+    /// ```
+    pub fn concat_ordered(mut self, mut other: Self) -> Self {
+        if self.is_empty() {
+            return other;
+        }
+        debug_assert!(self.last() < other.first() || other.is_empty());
+        Self::check_capacity(self.len() + other.len());
+        // The subarrays of `self` keep their values and pivots: a full last subarray is
+        // unchanged, and a partially full one is sorted, so the values of `other` can follow it
+        // once they are sorted.
+        other.sort_subarrays(0, other.min_indexes.len());
+        self.data.append(&mut other.data);
+        let subarray_count = get_subarray_idx_from_array_idx(self.data.len() - 1) + 1;
+        self.min_indexes.resize(subarray_count, 0);
+        debug_assert!(self.layout().assert_invariants());
+        self
+    }
+
     /// Truncates the sorted sequence, keeping the first `len` elements and dropping
     /// the rest.
     ///
@@ -889,12 +1802,12 @@ where
             // logical index corresponding to truncated length
             let index = len - 1;
             // find subarray containing logical index (we don't need to translate to raw index for this)
-            let subarray_idx = Self::get_subarray_idx_from_array_idx(index);
-            let subarray_offset = Self::get_array_idx_from_subarray_idx(subarray_idx);
+            let subarray_idx = get_subarray_idx_from_array_idx(index);
+            let subarray_offset = get_array_idx_from_subarray_idx(subarray_idx);
             let next_subarray_offset = if subarray_idx == self.min_indexes.len() - 1 {
                 self.data.len()
             } else {
-                Self::get_array_idx_from_subarray_idx(subarray_idx + 1)
+                get_array_idx_from_subarray_idx(subarray_idx + 1)
             };
             let subarray = &mut self.data[subarray_offset..next_subarray_offset];
             // sort subarray and update auxiliary arrays
@@ -903,11 +1816,86 @@ where
             self.min_indexes[subarray_idx] = 0;
             // now we can truncate the whole data array at the logical index
             self.data.truncate(len);
-            // trim auxiliary arrays
+            // trim auxiliary array
             self.min_indexes.truncate(subarray_idx + 1);
-            self.min_data.truncate(subarray_idx + 1);
         }
-        debug_assert!(self.assert_invariants());
+        debug_assert!(self.layout().assert_invariants());
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all elements `e` such that `f(&e)` returns `false`.
+    /// The elements are visited in ascending order.
+    ///
+    /// This is an `O(n)` operation, regardless of how many elements are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut set: RotatedArraySet<_> = (0..8).collect();
+    /// // Keep only the even numbers.
+    /// set.retain(|&k| k % 2 == 0);
+    /// assert!(set.iter().eq([0, 2, 4, 6].iter()));
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        // once the data array is sorted, we can compact it in place and rebuild the auxiliary array
+        self.sort_subarrays(0, self.min_indexes.len());
+        // `Vec::retain` keeps the unvisited elements if `f` panics, so the data stays sorted
+        RetainGuard::new(&mut self.data, &mut self.min_indexes)
+            .data
+            .retain(|x| f(x));
+        debug_assert!(self.layout().assert_invariants());
+    }
+
+    /// Creates an iterator that visits all values in ascending order and uses a closure
+    /// to determine if a value should be removed.
+    ///
+    /// If the closure returns `true`, the value is removed from the set and yielded.
+    /// If the closure returns `false`, or panics, the value remains in the set and
+    /// will not be yielded.
+    ///
+    /// If the returned `ExtractIf` is not exhausted, e.g. because it is dropped without
+    /// iterating or the iteration short-circuits, then the remaining values will be retained.
+    /// Use [`retain`] with a negated predicate if you do not need the returned iterator.
+    /// If the returned `ExtractIf` is leaked (e.g. with `mem::forget`), the set is left empty.
+    ///
+    /// Creating the iterator takes `O(n)` time, as does dropping it, and each value
+    /// visited takes constant time.
+    ///
+    /// [`retain`]: struct.RotatedArraySet.html#method.retain
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut set: RotatedArraySet<i32> = (0..8).collect();
+    /// let evens: RotatedArraySet<_> = set.extract_if(|v| v % 2 == 0).collect();
+    /// let odds = set;
+    /// assert_eq!(evens.into_iter().collect::<Vec<_>>(), vec![0, 2, 4, 6]);
+    /// assert_eq!(odds.into_iter().collect::<Vec<_>>(), vec![1, 3, 5, 7]);
+    /// ```
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, T, F>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.sort_subarrays(0, self.min_indexes.len());
+        // leave a valid empty set behind in case the iterator is leaked
+        self.min_indexes.clear();
+        // converting a `Vec` to a `VecDeque` never reallocates or moves elements
+        let deque = VecDeque::from(mem::take(&mut self.data));
+        let remaining = deque.len();
+        ExtractIf {
+            set: self,
+            deque,
+            remaining,
+            pred,
+        }
     }
 
     /// Returns the number of elements in the set.
@@ -982,7 +1970,32 @@ where
     /// assert_eq!(set_iter.next(), None);
     /// ```
     pub fn iter(&self) -> Iter<'_, T> {
-        Iter::new(Range::new(self))
+        Iter::new(Range::new(self.layout()))
+    }
+
+    /// Gets a double-ended iterator over the values in the set as sorted slices,
+    /// in ascending order. Concatenating the slices gives the same sequence as [`iter`].
+    ///
+    /// Since each of the `O(√n)` subarrays is split into at most two slices by its pivot,
+    /// this allows bulk processing of the set's values at slice speed.
+    ///
+    /// [`iter`]: struct.RotatedArraySet.html#method.iter
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut set = RotatedArraySet::new();
+    /// for i in (0..100).rev() {
+    ///     set.insert(i);
+    /// }
+    /// let sum: u32 = set.chunks().map(|chunk| chunk.iter().sum::<u32>()).sum();
+    /// assert_eq!(sum, 4950);
+    /// assert!(set.chunks().flatten().eq(set.iter()));
+    /// ```
+    pub fn chunks(&self) -> Chunks<'_, T> {
+        Chunks::new(Range::new(self.layout()))
     }
 
     /// Constructs a double-ended iterator over a sub-range of elements in the set.
@@ -992,6 +2005,10 @@ where
     /// `range((Excluded(4), Included(10)))` will yield a left-exclusive, right-inclusive
     /// range from 4 to 10.
     ///
+    /// The bounds may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
     /// # Examples
     ///
     /// ```
@@ -1016,61 +2033,195 @@ where
     /// let range: Vec<_> = set.range((Included(&4), Excluded(&8))).cloned().collect();
     /// assert_eq!(range, vec![4, 5, 6, 7]);
     /// ```
-    pub fn range<R>(&self, range: R) -> Iter<'_, T>
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    /// use std::ops::Bound::{Included, Unbounded};
+    ///
+    /// let set: RotatedArraySet<String> = vec!["a".into(), "b".into(), "c".into()].into();
+    /// let range: Vec<_> = set.range::<str, _>((Included("b"), Unbounded)).collect();
+    /// assert_eq!(range, ["b", "c"]);
+    /// ```
+    pub fn range<K, R>(&self, range: R) -> Iter<'_, T>
     where
-        R: RangeBounds<T>,
+        T: Borrow<K>,
+        K: Ord + ?Sized,
+        R: RangeBounds<K>,
     {
-        let range = self.get_range(range);
+        let range = self.layout().get_range(range);
         Iter::new(range)
     }
 
-    fn get_range<R>(&self, range: R) -> Range<'_, T>
+    /// Removes the specified range of values from the set in bulk, returning all removed
+    /// values as an iterator (in ascending order).
+    /// The range may be specified as for [`range`].
+    ///
+    /// The values are removed even if the iterator is not consumed.
+    ///
+    /// This is an `O(n)` operation, regardless of how many values are removed.
+    ///
+    /// The bounds may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// [`range`]: struct.RotatedArraySet.html#method.range
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut set: RotatedArraySet<_> = (1..=10).collect();
+    /// let expired: Vec<_> = set.drain(..=4).collect();
+    /// assert_eq!(expired, [1, 2, 3, 4]);
+    /// assert_eq!(set.first(), Some(&5));
+    /// ```
+    pub fn drain<K, R>(&mut self, range: R) -> Drain<'_, T>
     where
-        R: RangeBounds<T>,
+        T: Borrow<K>,
+        K: Ord + ?Sized,
+        R: RangeBounds<K>,
     {
-        match (range.start_bound(), range.end_bound()) {
-            (Excluded(s), Excluded(e)) if s == e => {
-                panic!("range start and end are equal and excluded in RotatedArraySet")
-            }
-            (Included(s), Included(e))
-            | (Included(s), Excluded(e))
-            | (Excluded(s), Included(e))
-            | (Excluded(s), Excluded(e))
-                if s > e =>
-            {
-                panic!("range start is greater than range end in RotatedArraySet")
-            }
-            _ => {}
-        };
-        let start_index_inclusive = match range.start_bound() {
+        let range = self.layout().get_range(range);
+        let (start, end) = (range.start_index_inclusive, range.end_index_exclusive);
+        self.drain_logical(start, end)
+    }
+
+    /// Removes the values with the specified range of ranks from the set in bulk,
+    /// returning all removed values as an iterator (in ascending order).
+    ///
+    /// The values are removed even if the iterator is not consumed.
+    ///
+    /// This is an `O(n)` operation, regardless of how many values are removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting rank is greater than the end rank,
+    /// or if the end rank is greater than the length of the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut set: RotatedArraySet<_> = vec![10, 20, 30, 40].into();
+    /// let smallest: Vec<_> = set.drain_ranks(..2).collect();
+    /// assert_eq!(smallest, [10, 20]);
+    /// assert!(set.iter().eq([30, 40].iter()));
+    /// ```
+    pub fn drain_ranks<R>(&mut self, range: R) -> Drain<'_, T>
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = match range.start_bound() {
             Unbounded => 0,
-            Included(s) => match self.find_raw_index(s) {
-                Ok(index) => index,
-                Err(index) => index,
-            },
-            Excluded(s) => match self.find_raw_index(s) {
-                Ok(index) => index + 1,
-                Err(index) => index,
-            },
+            Included(&s) => s,
+            Excluded(&s) => s
+                .checked_add(1)
+                .expect("range start overflow in RotatedArraySet"),
         };
-        let end_index_exclusive = match range.end_bound() {
+        let end = match range.end_bound() {
             Unbounded => self.len(),
-            Included(e) => match self.find_raw_index(e) {
-                Ok(index) => index + 1,
-                Err(index) => index,
-            },
-            Excluded(e) => match self.find_raw_index(e) {
-                Ok(index) => index,
-                Err(index) => index,
-            },
+            Included(&e) => e
+                .checked_add(1)
+                .expect("range end overflow in RotatedArraySet"),
+            Excluded(&e) => e,
+        };
+        assert!(
+            start <= end,
+            "range start is greater than range end in RotatedArraySet"
+        );
+        assert!(
+            end <= self.len(),
+            "range end is out of bounds in RotatedArraySet"
+        );
+        self.drain_logical(start, end)
+    }
+
+    /// Removes the specified range of values from the set in bulk, returning
+    /// the number of values removed.
+    /// The range may be specified as for [`range`].
+    ///
+    /// This is an `O(n)` operation, regardless of how many values are removed.
+    ///
+    /// The bounds may be any borrowed form of the set's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// [`range`]: struct.RotatedArraySet.html#method.range
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let mut set: RotatedArraySet<_> = (1..=10).collect();
+    /// assert_eq!(set.remove_range(3..8), 5);
+    /// assert!(set.iter().eq([1, 2, 8, 9, 10].iter()));
+    /// ```
+    pub fn remove_range<K, R>(&mut self, range: R) -> usize
+    where
+        T: Borrow<K>,
+        K: Ord + ?Sized,
+        R: RangeBounds<K>,
+    {
+        self.drain(range).len()
+    }
+
+    // Removes the elements with logical indexes in the given (half-open) range.
+    fn drain_logical(&mut self, start: usize, end: usize) -> Drain<'_, T> {
+        debug_assert!(start <= end && end <= self.len());
+        if start == end {
+            // drain an empty range at the end, so that a leaked iterator removes nothing
+            let (len, subarray_count) = (self.len(), self.min_indexes.len());
+            return Drain {
+                iter: self.data.drain(len..),
+                min_indexes: &mut self.min_indexes,
+                subarray_count,
+            };
+        }
+        // sort all subarrays from the start of the range onward, since they will all be shifted;
+        // the subarrays preceding the range are unaffected.
+        let first_subarray_idx = get_subarray_idx_from_array_idx(start);
+        self.sort_subarrays(first_subarray_idx, self.min_indexes.len());
+        // The auxiliary array only depends on the length, so size it for the values before
+        // the range, which are all that remain if the iterator is leaked, and resize it for
+        // the final length when the iterator is dropped.
+        let subarray_count = |len: usize| {
+            if len == 0 {
+                0
+            } else {
+                get_subarray_idx_from_array_idx(len - 1) + 1
+            }
         };
-        Range::with_bounds(self, start_index_inclusive, end_index_exclusive)
+        let new_len = self.len() - (end - start);
+        self.min_indexes.truncate(first_subarray_idx);
+        self.min_indexes.resize(subarray_count(start), 0);
+        Drain {
+            iter: self.data.drain(start..end),
+            min_indexes: &mut self.min_indexes,
+            subarray_count: subarray_count(new_len),
+        }
     }
 
     /// Visits the values representing the difference,
     /// i.e., the values that are in `self` but not in `other`,
     /// in ascending order.
     ///
+    /// Each set is searched by galloping over ranks, so visiting all `k` values
+    /// takes `O(m lg(n/m + 1) + k)` time, where `m` and `n` are the lengths of
+    /// the smaller and larger set respectively.
+    ///
     /// # Examples
     ///
     /// ```
@@ -1088,16 +2239,17 @@ where
     /// assert_eq!(diff, [1]);
     /// ```
     pub fn difference<'a>(&'a self, other: &'a RotatedArraySet<T>) -> Difference<'a, T> {
-        Difference {
-            self_iter: self.iter(),
-            other_set: other,
-        }
+        Difference::new(self.layout(), other.layout())
     }
 
     /// Visits the values representing the symmetric difference,
     /// i.e., the values that are in `self` or in `other` but not in both,
     /// in ascending order.
     ///
+    /// Each set is searched by galloping over ranks, so visiting all `k` values
+    /// takes `O(m lg(n/m + 1) + k)` time, where `m` and `n` are the lengths of
+    /// the smaller and larger set respectively.
+    ///
     /// # Examples
     ///
     /// ```
@@ -1118,16 +2270,17 @@ where
         &'a self,
         other: &'a RotatedArraySet<T>,
     ) -> SymmetricDifference<'a, T> {
-        SymmetricDifference {
-            a: self.iter().peekable(),
-            b: other.iter().peekable(),
-        }
+        SymmetricDifference::new(self.layout(), other.layout())
     }
 
     /// Visits the values representing the intersection,
     /// i.e., the values that are both in `self` and `other`,
     /// in ascending order.
     ///
+    /// Only the range where the two sets overlap is visited, and each set is searched
+    /// by galloping over ranks, so visiting all values takes `O(m lg(n/m + 1))` time,
+    /// where `m` and `n` are the lengths of the smaller and larger set respectively.
+    ///
     /// # Examples
     ///
     /// ```
@@ -1145,22 +2298,17 @@ where
     /// assert_eq!(intersection, [2]);
     /// ```
     pub fn intersection<'a>(&'a self, other: &'a RotatedArraySet<T>) -> Intersection<'a, T> {
-        let (small, other) = if self.len() <= other.len() {
-            (self, other)
-        } else {
-            (other, self)
-        };
-        // Iterate the small set, searching for matches in the large set.
-        Intersection {
-            small_iter: small.iter(),
-            large_set: other,
-        }
+        Intersection::new(self.layout(), other.layout())
     }
 
     /// Visits the values representing the union,
     /// i.e., all the values in `self` or `other`, without duplicates,
     /// in ascending order.
     ///
+    /// Runs of values from either set are found by galloping over ranks, so visiting
+    /// all `k` values takes `O(k)` time but only `O(m lg(n/m + 1))` comparisons,
+    /// where `m` and `n` are the lengths of the smaller and larger set respectively.
+    ///
     /// # Examples
     ///
     /// ```
@@ -1178,56 +2326,252 @@ where
     /// assert_eq!(union, [1, 2, 3]);
     /// ```
     pub fn union<'a>(&'a self, other: &'a RotatedArraySet<T>) -> Union<'a, T> {
-        Union {
-            a: self.iter().peekable(),
-            b: other.iter().peekable(),
+        Union::new(self.layout(), other.layout())
+    }
+
+    fn layout(&self) -> Layout<'_, T> {
+        Layout::new(&self.data, &self.min_indexes)
+    }
+
+    // The largest length whose subarray offsets, including the end offset of the
+    // last subarray, can all be represented in a `usize`.
+    const MAX_LEN: usize = integer_sum(integer_sum_inverse(usize::MAX));
+
+    fn check_capacity(len: usize) {
+        if len > Self::MAX_LEN {
+            panic!("capacity overflow");
         }
     }
 
-    fn integer_sum(n: usize) -> usize {
-        // I learned this from a 10-year-old named Gauss
-        (n * (n + 1)) / 2
+    // given data array, sort and deduplicate it (keeping the first of each run of
+    // equal elements), then initialize auxiliary arrays
+    fn init(&mut self) {
+        // a stable sort is required to keep the first occurrence of each element
+        self.data.sort();
+        self.data.dedup();
+        self.init_min_indexes();
     }
 
-    fn integer_sum_inverse(n: usize) -> usize {
-        // y = (x * (x + 1)) / 2
-        // x = (sqrt(8 * y + 1) - 1) / 2
-        let floaty = ((n as f64 * 8.0 + 1.0).sqrt() - 1.0) / 2.0;
-        let tmp = floaty as usize;
-        let sum = Self::integer_sum(tmp);
-        if sum <= n {
-            tmp
-        } else {
-            tmp - 1
+    // given sorted data array, initialize auxiliary arrays
+    fn init_min_indexes(&mut self) {
+        Self::check_capacity(self.data.len());
+        self.min_indexes.clear();
+        if !self.data.is_empty() {
+            let last_subarray_idx = get_subarray_idx_from_array_idx(self.data.len() - 1);
+            // reuse any capacity already reserved
+            self.min_indexes.resize(last_subarray_idx + 1, 0);
         }
+        // check only once the auxiliary array matches the data array, in case a comparison panics
+        debug_assert!(IsSorted::is_sorted(&mut self.data.iter()));
     }
 
-    fn get_subarray_idx_from_array_idx(idx: usize) -> usize {
-        if idx == 0 {
-            0
-        } else {
-            Self::integer_sum_inverse(idx)
+    // rotate the subarrays in the given (half-open) range into sorted order,
+    // so that raw and logical indexes coincide within those subarrays
+    fn sort_subarrays(&mut self, first_subarray_idx: usize, end_subarray_idx: usize) {
+        sort_subarrays(
+            &mut self.data,
+            &mut self.min_indexes,
+            first_subarray_idx,
+            end_subarray_idx,
+        );
+    }
+
+    // Removes and returns the element at the given raw index.
+    fn remove_raw(&mut self, raw_idx: usize) -> T {
+        remove_raw(&mut self.data, &mut self.min_indexes, raw_idx)
+    }
+}
+
+impl<'a, T> Layout<'a, T>
+where
+    T: Ord,
+{
+    fn new(data: &'a [T], min_indexes: &'a [usize]) -> Layout<'a, T> {
+        Layout { data, min_indexes }
+    }
+
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn first(&self) -> Option<&'a T> {
+        // the first subarray has a single element
+        self.data.first()
+    }
+
+    fn last(&self) -> Option<&'a T> {
+        self.select(self.data.len().checked_sub(1)?)
+    }
+
+    fn select(&self, rank: usize) -> Option<&'a T> {
+        if rank >= self.data.len() {
+            return None;
         }
+        let raw_idx = self.get_raw_idx_from_logical_idx(rank);
+        Some(&self.data[raw_idx])
     }
 
-    fn get_array_idx_from_subarray_idx(idx: usize) -> usize {
-        if idx == 0 {
-            0
-        } else {
-            Self::integer_sum(idx)
+    // Returns the bounds of the subarray containing the given logical or raw index.
+    fn subarray_bounds_of(&self, idx: usize) -> SubarrayBounds {
+        debug_assert!(idx < self.data.len());
+        let subarray_idx = get_subarray_idx_from_array_idx(idx);
+        self.subarray_bounds(subarray_idx)
+    }
+
+    fn subarray_bounds(&self, subarray_idx: usize) -> SubarrayBounds {
+        let start = get_array_idx_from_subarray_idx(subarray_idx);
+        // only the last subarray may be partially full
+        let len = min(subarray_idx + 1, self.data.len() - start);
+        SubarrayBounds {
+            idx: subarray_idx,
+            start,
+            len,
+        }
+    }
+
+    fn next_subarray_bounds(&self, bounds: SubarrayBounds) -> SubarrayBounds {
+        let start = bounds.start + bounds.len;
+        debug_assert!(start < self.data.len());
+        SubarrayBounds {
+            idx: bounds.idx + 1,
+            start,
+            len: min(bounds.idx + 2, self.data.len() - start),
+        }
+    }
+
+    fn prev_subarray_bounds(&self, bounds: SubarrayBounds) -> SubarrayBounds {
+        debug_assert!(bounds.idx > 0);
+        // every subarray but the last is full
+        SubarrayBounds {
+            idx: bounds.idx - 1,
+            start: bounds.start - bounds.idx,
+            len: bounds.idx,
         }
     }
 
     fn is_last_subarray_full(&self) -> bool {
-        self.data.len() == Self::get_array_idx_from_subarray_idx(self.min_indexes.len())
+        self.data.len() == get_array_idx_from_subarray_idx(self.min_indexes.len())
+    }
+
+    // Returns a reference to the minimum (pivot) element of the given subarray.
+    fn subarray_min(&self, subarray_idx: usize) -> &'a T {
+        let subarray_offset = get_array_idx_from_subarray_idx(subarray_idx);
+        &self.data[subarray_offset + self.min_indexes[subarray_idx]]
+    }
+
+    // Binary searches the subarray minimums, with the same semantics as `slice::binary_search_by`.
+    // (We don't store the minimums separately, since that would require cloning elements.)
+    fn search_subarray_mins_by<F>(&self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        let mut low = 0;
+        let mut high = self.min_indexes.len();
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match f(self.subarray_min(mid)) {
+                Less => low = mid + 1,
+                Greater => high = mid,
+                Equal => return Ok(mid),
+            }
+        }
+        Err(low)
+    }
+
+    // Like `rank`, but with the comparator semantics of `find_raw_index_by`.
+    fn rank_by<F>(&self, f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        match self.find_raw_index_by(f) {
+            Ok(index) => Ok(self.get_logical_idx_from_raw_idx(index)),
+            Err(index) => Err(self.get_logical_idx_from_raw_idx(index)),
+        }
+    }
+
+    // Returns the raw insertion point before the first element whose key is not less than `target`.
+    fn lower_bound_by_key<K, F>(&self, target: &K, key: F) -> usize
+    where
+        K: Ord + ?Sized,
+        F: Fn(&T) -> &K,
+    {
+        // the comparator never returns `Equal`, so the search always ends at an insertion point
+        match self.find_raw_index_by(|x| if key(x) < target { Less } else { Greater }) {
+            Ok(_) => unreachable!(),
+            Err(index) => index,
+        }
+    }
+
+    // Returns the raw insertion point after the last element whose key is not greater than `target`.
+    fn upper_bound_by_key<K, F>(&self, target: &K, key: F) -> usize
+    where
+        K: Ord + ?Sized,
+        F: Fn(&T) -> &K,
+    {
+        match self.find_raw_index_by(|x| if key(x) <= target { Less } else { Greater }) {
+            Ok(_) => unreachable!(),
+            Err(index) => index,
+        }
+    }
+
+    // Translates a logical index (i.e., rank) into the raw index of the corresponding element.
+    fn get_raw_idx_from_logical_idx(&self, logical_index: usize) -> usize {
+        debug_assert!(logical_index < self.data.len());
+        let subarray_idx = get_subarray_idx_from_array_idx(logical_index);
+        let subarray_start_idx = get_array_idx_from_subarray_idx(subarray_idx);
+        let subarray_len = if subarray_idx == self.min_indexes.len() - 1 {
+            self.data.len() - subarray_start_idx
+        } else {
+            subarray_idx + 1
+        };
+        debug_assert!(logical_index >= subarray_start_idx);
+        let idx_offset = logical_index - subarray_start_idx;
+        let pivot_offset = self.min_indexes[subarray_idx];
+        let rotated_offset = (pivot_offset + idx_offset) % subarray_len;
+        debug_assert!(rotated_offset < subarray_len);
+        subarray_start_idx + rotated_offset
+    }
+
+    // Translates a raw index (or insertion point) into the corresponding logical index (i.e., rank).
+    fn get_logical_idx_from_raw_idx(&self, raw_index: usize) -> usize {
+        if raw_index == self.data.len() {
+            return raw_index;
+        }
+        debug_assert!(raw_index < self.data.len());
+        let subarray_idx = get_subarray_idx_from_array_idx(raw_index);
+        let subarray_start_idx = get_array_idx_from_subarray_idx(subarray_idx);
+        let subarray_len = if subarray_idx == self.min_indexes.len() - 1 {
+            self.data.len() - subarray_start_idx
+        } else {
+            subarray_idx + 1
+        };
+        let pivot_idx = subarray_start_idx + self.min_indexes[subarray_idx];
+        if raw_index >= pivot_idx {
+            subarray_start_idx + raw_index - pivot_idx
+        } else {
+            subarray_start_idx + subarray_len - (pivot_idx - raw_index)
+        }
     }
 
     // Returns either (raw) index of element if it exists, or (raw) insertion point if it doesn't exist.
-    fn find_raw_index(&self, value: &T) -> Result<usize, usize> {
+    fn find_raw_index<Q>(&self, value: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find_raw_index_by(|x| x.borrow().cmp(value))
+    }
+
+    // Like `find_raw_index`, but with the same comparator semantics as `slice::binary_search_by`:
+    // `f` returns the ordering of its argument relative to the target.
+    fn find_raw_index_by<F>(&self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
         if self.data.is_empty() {
             return Err(0);
         }
-        // find two candidate subarrays by binary searching self.min_data,
+        // find two candidate subarrays by binary searching the subarray minimums,
         // then compare value to max value of first subarray, if it's smaller
         // then binary search first subarray, otherwise second subarray
         // TODO: actually we only need to binary search first subarray, max
@@ -1236,10 +2580,10 @@ where
         // if element greater than max of first subarray but less than min of
         // second subarray, just return insertion point on min index of second subarray.
         debug_assert!(self.assert_invariants());
-        match self.min_data.binary_search(value) {
+        match self.search_subarray_mins_by(&mut f) {
             Ok(idx) => {
                 // `value` is located directly on a pivot index
-                let found_idx = Self::get_array_idx_from_subarray_idx(idx) + self.min_indexes[idx];
+                let found_idx = get_array_idx_from_subarray_idx(idx) + self.min_indexes[idx];
                 debug_assert!(found_idx < self.len());
                 Ok(found_idx)
             }
@@ -1256,18 +2600,17 @@ where
                 } else {
                     // we can assume the predecessor subarray is full
                     let prev_max_idx = if self.min_indexes[idx - 1] == 0 {
-                        Self::get_array_idx_from_subarray_idx(idx) - 1
+                        get_array_idx_from_subarray_idx(idx) - 1
                     } else {
-                        Self::get_array_idx_from_subarray_idx(idx - 1) + self.min_indexes[idx - 1]
-                            - 1
+                        get_array_idx_from_subarray_idx(idx - 1) + self.min_indexes[idx - 1] - 1
                     };
-                    if *value <= self.data[prev_max_idx] {
+                    if f(&self.data[prev_max_idx]) != Less {
                         idx - 1
                     } else {
                         idx
                     }
                 };
-                let subarray_offset = Self::get_array_idx_from_subarray_idx(subarray_idx);
+                let subarray_offset = get_array_idx_from_subarray_idx(subarray_idx);
                 // we may need to create a new subarray to insert this element
                 debug_assert!(subarray_offset <= self.data.len());
                 if subarray_offset == self.data.len() {
@@ -1277,7 +2620,7 @@ where
                 let next_subarray_offset = if subarray_idx == self.min_indexes.len() - 1 {
                     self.data.len()
                 } else {
-                    Self::get_array_idx_from_subarray_idx(subarray_idx + 1)
+                    get_array_idx_from_subarray_idx(subarray_idx + 1)
                 };
                 // split subarray into two slices separated by pivot,
                 // and search both separately.
@@ -1288,7 +2631,10 @@ where
                 debug_assert!(
                     IsSorted::is_sorted(&mut left.iter()) && IsSorted::is_sorted(&mut right.iter())
                 );
-                match (left.binary_search(value), right.binary_search(value)) {
+                match (
+                    left.binary_search_by(&mut f),
+                    right.binary_search_by(&mut f),
+                ) {
                     (Ok(idx), _) => Ok(subarray_offset + idx),
                     (_, Ok(idx)) => Ok(subarray_pivot + idx),
                     // if right insertion point is past right subarray, and left subarray is not empty, then true insertion point must be on left
@@ -1311,62 +2657,347 @@ where
 
     #[inline(always)]
     fn assert_invariants(&self) -> bool {
-        // assert order
-        assert!(IsSorted::is_sorted(&mut self.min_data.iter()));
-        let mut min_data_dedup = self.min_data.clone();
-        min_data_dedup.dedup();
-        // assert uniqueness
-        assert!(self.min_data[..] == min_data_dedup[..]);
         // assert index of each subarray's minimum lies within the subarray
         assert!(self
             .min_indexes
             .iter()
             .enumerate()
-            .all(|(idx, &offset)| offset <= idx));
-        // assert min_data is properly synchronized with min_indexes and self.data
-        assert!(self
-            .min_indexes
-            .iter()
-            .enumerate()
-            .all(|(idx, &offset)| self.min_data[idx]
-                == self.data[Self::get_array_idx_from_subarray_idx(idx) + offset]));
-        // assert min_indexes holds the index of the actual minimum of each subarray
+            .all(|(idx, &offset)| offset <= idx
+                && get_array_idx_from_subarray_idx(idx) + offset < self.data.len()));
+        // assert each subarray is sorted when read from its pivot, and that no element
+        // of a subarray exceeds the minimum of its successor. (This allows duplicates,
+        // which `RotatedArrayMultiset` relies on; the set enforces uniqueness on insertion.)
         for i in 0..self.min_indexes.len() {
-            let subarray_begin_idx = Self::get_array_idx_from_subarray_idx(i);
-            let subarray_end_idx = min(
-                self.data.len(),
-                Self::get_array_idx_from_subarray_idx(i + 1),
-            );
+            let subarray_begin_idx = get_array_idx_from_subarray_idx(i);
+            let subarray_end_idx = min(self.data.len(), get_array_idx_from_subarray_idx(i + 1));
             let subarray = &self.data[subarray_begin_idx..subarray_end_idx];
-            let min_idx = subarray
-                .iter()
-                .enumerate()
-                .min_by(|&(_, v1), &(_, v2)| v1.cmp(v2))
-                .unwrap()
-                .0;
-            assert!(min_idx == self.min_indexes[i]);
+            let (left, right) = subarray.split_at(self.min_indexes[i]);
+            assert!(IsSorted::is_sorted(&mut right.iter().chain(left.iter())));
+            if i + 1 < self.min_indexes.len() {
+                let max = if left.is_empty() {
+                    right.last()
+                } else {
+                    left.last()
+                };
+                assert!(max.unwrap() <= self.subarray_min(i + 1));
+            }
         }
         true
     }
 
-    // given data array, initialize auxiliary arrays
-    fn init(&mut self) {
-        debug_assert!(self.min_indexes.is_empty() && self.min_data.is_empty());
-        if !self.data.is_empty() {
-            self.data.sort_unstable(); // don't want to allocate
-            let last_subarray_idx = Self::get_subarray_idx_from_array_idx(self.data.len() - 1);
-            self.min_indexes = vec![0; last_subarray_idx + 1];
-            for subarray_idx in 0..=last_subarray_idx {
-                let subarray_offset = Self::get_array_idx_from_subarray_idx(subarray_idx);
-                self.min_data.push(self.data[subarray_offset]);
+    fn get_range<K, R>(&self, range: R) -> Range<'a, T>
+    where
+        T: Borrow<K>,
+        K: Ord + ?Sized,
+        R: RangeBounds<K>,
+    {
+        self.get_range_by_key(range, |x| x.borrow())
+    }
+
+    // Resolves range bounds on the key extracted by `key` to logical indexes.
+    fn get_range_by_key<K, R, F>(&self, range: R, key: F) -> Range<'a, T>
+    where
+        K: Ord + ?Sized,
+        R: RangeBounds<K>,
+        F: Fn(&T) -> &K,
+    {
+        match (range.start_bound(), range.end_bound()) {
+            (Excluded(s), Excluded(e)) if s == e => {
+                panic!("range start and end are equal and excluded in RotatedArraySet")
+            }
+            (Included(s), Included(e))
+            | (Included(s), Excluded(e))
+            | (Excluded(s), Included(e))
+            | (Excluded(s), Excluded(e))
+                if s > e =>
+            {
+                panic!("range start is greater than range end in RotatedArraySet")
             }
+            _ => {}
+        };
+        // NB: bounds must be translated to logical indexes, which only coincide
+        // with raw indexes when no subarray is rotated. We use lower and upper
+        // bounds rather than exact matches so that duplicates are handled correctly.
+        let start_index_inclusive = match range.start_bound() {
+            Unbounded => 0,
+            Included(s) => self.get_logical_idx_from_raw_idx(self.lower_bound_by_key(s, &key)),
+            Excluded(s) => self.get_logical_idx_from_raw_idx(self.upper_bound_by_key(s, &key)),
+        };
+        let end_index_exclusive = match range.end_bound() {
+            Unbounded => self.len(),
+            Included(e) => self.get_logical_idx_from_raw_idx(self.upper_bound_by_key(e, &key)),
+            Excluded(e) => self.get_logical_idx_from_raw_idx(self.lower_bound_by_key(e, &key)),
+        };
+        Range::with_bounds(*self, start_index_inclusive, end_index_exclusive)
+    }
+}
+
+// The operations on the data and auxiliary arrays that need more than slice access,
+// so that insertion and removal can run on a `Vec` or on the inline storage of an
+// `ArrayRotatedSet`.
+trait Buffer<T>: DerefMut<Target = [T]> {
+    fn insert(&mut self, index: usize, value: T);
+    fn remove(&mut self, index: usize) -> T;
+    fn push(&mut self, value: T);
+    fn pop(&mut self) -> Option<T>;
+}
+
+impl<T> Buffer<T> for Vec<T> {
+    fn insert(&mut self, index: usize, value: T) {
+        Vec::insert(self, index, value)
+    }
+
+    fn remove(&mut self, index: usize) -> T {
+        Vec::remove(self, index)
+    }
+
+    fn push(&mut self, value: T) {
+        Vec::push(self, value)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        Vec::pop(self)
+    }
+}
+
+// Inserts a value at the given raw insertion point, which the caller must have checked
+// against the capacity of the storage.
+fn insert_raw<T, D, M>(data: &mut D, min_indexes: &mut M, insert_idx: usize, value: T)
+where
+    T: Ord,
+    D: Buffer<T>,
+    M: Buffer<usize>,
+{
+    // find subarray containing this insertion point
+    let subarray_idx = get_subarray_idx_from_array_idx(insert_idx);
+    // inserted element could be in a new subarray
+    debug_assert!(subarray_idx <= min_indexes.len());
+    // create a new subarray if necessary
+    if subarray_idx == min_indexes.len() {
+        min_indexes.push(0);
+    }
+    let subarray_offset = get_array_idx_from_subarray_idx(subarray_idx);
+    // if insertion point is in last subarray and last subarray isn't full, just insert the new element
+    if subarray_idx == min_indexes.len() - 1
+        && !Layout::new(data, min_indexes).is_last_subarray_full()
+    {
+        // Since we always insert into a partially full subarray in sorted order,
+        // there is no need to update the pivot location.
+        debug_assert!(min_indexes[subarray_idx] == 0);
+        data.insert(insert_idx, value);
+        debug_assert!(Layout::new(data, min_indexes).assert_invariants());
+        return;
+    }
+    // From now on, we can assume that the subarray we're inserting into is always full.
+    let next_subarray_offset = get_array_idx_from_subarray_idx(subarray_idx + 1);
+    let subarray = &mut data[subarray_offset..next_subarray_offset];
+    let pivot_offset = min_indexes[subarray_idx];
+    let insert_offset = insert_idx - subarray_offset;
+    let max_offset = if pivot_offset == 0 {
+        subarray.len() - 1
+    } else {
+        pivot_offset - 1
+    };
+    // this logic is best understood with a diagram of a rotated array, e.g.:
+    //
+    // ------------------------------------------------------------------------
+    // | 12 | 13 | 14 | 15 | 16 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9 | 10 | 11 |
+    // ------------------------------------------------------------------------
+    //
+    // We never copy elements: the max is rotated into the slot where the new
+    // value belongs, and then swapped out for the new value.
+    let mut prev_max = if max_offset < pivot_offset && insert_offset >= pivot_offset {
+        subarray[max_offset..insert_offset].rotate_left(1);
+        min_indexes[subarray_idx] = max_offset;
+        mem::replace(&mut subarray[insert_offset - 1], value)
+    } else {
+        subarray[insert_offset..=max_offset].rotate_right(1);
+        mem::replace(&mut subarray[insert_offset], value)
+    };
+    debug_assert!(Layout::new(data, min_indexes).assert_invariants());
+    let max_subarray_idx = min_indexes.len() - 1;
+    let next_subarray_idx = subarray_idx + 1;
+    let last_subarray_full = Layout::new(data, min_indexes).is_last_subarray_full();
+    // now loop over all remaining subarrays, setting the min (pivot) of each to the max of its predecessor
+    for (i, pivot_offset_ref) in min_indexes[next_subarray_idx..].iter_mut().enumerate() {
+        let cur_subarray_idx = next_subarray_idx + i;
+        // if the last subarray isn't full, skip it
+        if cur_subarray_idx == max_subarray_idx && !last_subarray_full {
+            break;
+        }
+        let max_offset = if *pivot_offset_ref == 0 {
+            cur_subarray_idx
+        } else {
+            *pivot_offset_ref - 1
+        };
+        let max_idx = max_offset + get_array_idx_from_subarray_idx(cur_subarray_idx);
+        mem::swap(&mut data[max_idx], &mut prev_max);
+        *pivot_offset_ref = max_offset;
+    }
+    // if the last subarray was full, append current max to a new subarray, otherwise insert max in sorted order
+    if last_subarray_full {
+        data.push(prev_max);
+        min_indexes.push(0);
+    } else {
+        let max_subarray_offset = get_array_idx_from_subarray_idx(max_subarray_idx);
+        // since `max` is guaranteed to be <= the pivot value, we always insert it at the pivot location
+        debug_assert!(prev_max <= data[max_subarray_offset]);
+        data.insert(max_subarray_offset, prev_max);
+    }
+    debug_assert!(Layout::new(data, min_indexes).assert_invariants());
+}
+
+// Gives access to a sorted data array and, when dropped, rebuilds its auxiliary array
+// for whatever length the data array has by then, even if a predicate panicked midway.
+pub(crate) struct RetainGuard<'a, T> {
+    pub(crate) data: &'a mut Vec<T>,
+    min_indexes: &'a mut Vec<usize>,
+}
+
+impl<'a, T> RetainGuard<'a, T> {
+    pub(crate) fn new(data: &'a mut Vec<T>, min_indexes: &'a mut Vec<usize>) -> Self {
+        RetainGuard { data, min_indexes }
+    }
+}
+
+impl<T> Drop for RetainGuard<'_, T> {
+    fn drop(&mut self) {
+        self.min_indexes.clear();
+        // reuse any capacity already reserved
+        self.min_indexes.resize(subarray_count(self.data.len()), 0);
+    }
+}
+
+// Rotates the subarrays in the given (half-open) range into sorted order,
+// so that raw and logical indexes coincide within those subarrays.
+fn sort_subarrays<T>(
+    data: &mut [T],
+    min_indexes: &mut [usize],
+    first_subarray_idx: usize,
+    end_subarray_idx: usize,
+) {
+    for (i, pivot_offset_ref) in min_indexes[first_subarray_idx..end_subarray_idx]
+        .iter_mut()
+        .enumerate()
+    {
+        let subarray_idx = first_subarray_idx + i;
+        let subarray_start_idx = get_array_idx_from_subarray_idx(subarray_idx);
+        let subarray_end_idx = min(
+            data.len(),
+            get_array_idx_from_subarray_idx(subarray_idx + 1),
+        );
+        // sort subarray in-place
+        data[subarray_start_idx..subarray_end_idx].rotate_left(*pivot_offset_ref);
+        *pivot_offset_ref = 0;
+    }
+}
+
+// Removes and returns the element at the given raw index.
+fn remove_raw<T, D, M>(data: &mut D, min_indexes: &mut M, raw_idx: usize) -> T
+where
+    T: Ord,
+    D: Buffer<T>,
+    M: Buffer<usize>,
+{
+    let mut remove_idx = raw_idx;
+    let max_subarray_idx = min_indexes.len() - 1;
+    let max_subarray_offset = get_array_idx_from_subarray_idx(max_subarray_idx);
+    // find subarray containing the element to remove
+    let subarray_idx = get_subarray_idx_from_array_idx(remove_idx);
+    debug_assert!(subarray_idx <= max_subarray_idx);
+    let subarray_offset = get_array_idx_from_subarray_idx(subarray_idx);
+    // if the last subarray was rotated, sort it to maintain insert invariant
+    if Layout::new(data, min_indexes).is_last_subarray_full() {
+        let last_min_offset = min_indexes[max_subarray_idx];
+        // rotate left by the min offset instead of sorting
+        data[max_subarray_offset..].rotate_left(last_min_offset);
+        min_indexes[max_subarray_idx] = 0;
+        // the remove index changes after sorting the last subarray
+        if subarray_idx == max_subarray_idx {
+            let last_subarray_len = data.len() - max_subarray_offset;
+            let rotated_offset = remove_idx - max_subarray_offset + last_subarray_len;
+            remove_idx =
+                max_subarray_offset + (rotated_offset - last_min_offset) % last_subarray_len;
         }
     }
+    // if we're not removing an element in the last subarray, then we end up deleting its minimum,
+    // which is always at the first offset since it's sorted
+    let max_subarray_remove_idx = if subarray_idx == max_subarray_idx {
+        remove_idx
+    } else {
+        max_subarray_offset
+    };
+    // if removal point is not in last subarray, perform a "hard exchange"
+    if subarray_idx < max_subarray_idx {
+        // From now on, we can assume that the subarray we're removing from is full.
+        let next_subarray_offset = get_array_idx_from_subarray_idx(subarray_idx + 1);
+        let subarray = &mut data[subarray_offset..next_subarray_offset];
+        let pivot_offset = min_indexes[subarray_idx];
+        let remove_offset = remove_idx - subarray_offset;
+        let max_offset = if pivot_offset == 0 {
+            subarray.len() - 1
+        } else {
+            pivot_offset - 1
+        };
+        // this logic is best understood with a diagram of a rotated array, e.g.:
+        //
+        // ------------------------------------------------------------------------
+        // | 12 | 13 | 14 | 15 | 16 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9 | 10 | 11 |
+        // ------------------------------------------------------------------------
+        //
+        // We never copy elements: the removed element is rotated into the slot vacated
+        // by the shift, and then swapped down the subarrays until it reaches the last one.
+        let mut prev_max_offset = if max_offset < pivot_offset && remove_offset >= pivot_offset {
+            subarray[pivot_offset..=remove_offset].rotate_right(1);
+            let new_pivot_offset = if pivot_offset == subarray.len() - 1 {
+                0
+            } else {
+                pivot_offset + 1
+            };
+            min_indexes[subarray_idx] = new_pivot_offset;
+            pivot_offset
+        } else {
+            subarray[remove_offset..=max_offset].rotate_left(1);
+            max_offset
+        };
+        let next_subarray_idx = min(max_subarray_idx, subarray_idx + 1);
+        // now perform an "easy exchange" in all remaining subarrays except the last,
+        // setting the max of each to the min of its successor.
+        for (i, pivot_offset_ref) in min_indexes[next_subarray_idx..max_subarray_idx]
+            .iter_mut()
+            .enumerate()
+        {
+            let cur_subarray_idx = next_subarray_idx + i;
+            let cur_subarray_offset = get_array_idx_from_subarray_idx(cur_subarray_idx);
+            let prev_max_idx =
+                prev_max_offset + get_array_idx_from_subarray_idx(cur_subarray_idx - 1);
+            data.swap(prev_max_idx, cur_subarray_offset + *pivot_offset_ref);
+            prev_max_offset = *pivot_offset_ref;
+            let new_min_offset = if *pivot_offset_ref == cur_subarray_idx {
+                0
+            } else {
+                *pivot_offset_ref + 1
+            };
+            *pivot_offset_ref = new_min_offset;
+        }
+        // now we fix up the last subarray: we swap its minimum into the max position
+        // of the previous subarray, leaving the removed element in its place.
+        let prev_max_idx = prev_max_offset + get_array_idx_from_subarray_idx(max_subarray_idx - 1);
+        // since the last subarray is always sorted, its minimum element is always on the first offset
+        data.swap(prev_max_idx, max_subarray_offset);
+    }
+    let removed = data.remove(max_subarray_remove_idx);
+    // if last subarray is now empty, trim the auxiliary array
+    if max_subarray_offset == data.len() {
+        min_indexes.pop();
+    }
+    debug_assert!(Layout::new(data, min_indexes).assert_invariants());
+    removed
 }
 
 impl<T> PartialEq for RotatedArraySet<T>
 where
-    T: Ord + Copy + Default + Debug,
+    T: Ord,
 {
     fn eq(&self, other: &Self) -> bool {
         if self.len() != other.len() {
@@ -1381,71 +3012,200 @@ where
     }
 }
 
-impl<T> Eq for RotatedArraySet<T> where T: Ord + Copy + Default + Debug {}
+impl<T> Eq for RotatedArraySet<T> where T: Ord {}
+
+impl<T> Hash for RotatedArraySet<T>
+where
+    T: Ord + Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for i in 0..self.len() {
+            self.select(i).hash(state);
+        }
+    }
+}
+
+impl<T> PartialOrd for RotatedArraySet<T>
+where
+    T: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for RotatedArraySet<T>
+where
+    T: Ord,
+{
+    /// Compares two sets lexicographically by their values in ascending order,
+    /// like `BTreeSet`.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T> Sub<&RotatedArraySet<T>> for &RotatedArraySet<T>
+where
+    T: Ord + Clone,
+{
+    type Output = RotatedArraySet<T>;
+
+    /// Returns the difference of `self` and `rhs` as a new `RotatedArraySet<T>`.
+    ///
+    /// This is a linear-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let a: RotatedArraySet<_> = vec![1, 2, 3].into_iter().collect();
+    /// let b: RotatedArraySet<_> = vec![3, 4, 5].into_iter().collect();
+    ///
+    /// let result = &a - &b;
+    /// let result_vec: Vec<_> = result.into_iter().collect();
+    /// assert_eq!(result_vec, [1, 2]);
+    /// ```
+    fn sub(self, rhs: &RotatedArraySet<T>) -> RotatedArraySet<T> {
+        RotatedArraySet::from_sorted_dedup_iter(self.difference(rhs).cloned())
+    }
+}
+
+impl<T> BitXor<&RotatedArraySet<T>> for &RotatedArraySet<T>
+where
+    T: Ord + Clone,
+{
+    type Output = RotatedArraySet<T>;
+
+    /// Returns the symmetric difference of `self` and `rhs` as a new `RotatedArraySet<T>`.
+    ///
+    /// This is a linear-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let a: RotatedArraySet<_> = vec![1, 2, 3].into_iter().collect();
+    /// let b: RotatedArraySet<_> = vec![2, 3, 4].into_iter().collect();
+    ///
+    /// let result = &a ^ &b;
+    /// let result_vec: Vec<_> = result.into_iter().collect();
+    /// assert_eq!(result_vec, [1, 4]);
+    /// ```
+    fn bitxor(self, rhs: &RotatedArraySet<T>) -> RotatedArraySet<T> {
+        RotatedArraySet::from_sorted_dedup_iter(self.symmetric_difference(rhs).cloned())
+    }
+}
+
+impl<T> BitAnd<&RotatedArraySet<T>> for &RotatedArraySet<T>
+where
+    T: Ord + Clone,
+{
+    type Output = RotatedArraySet<T>;
 
-impl<T> Hash for RotatedArraySet<T>
+    /// Returns the intersection of `self` and `rhs` as a new `RotatedArraySet<T>`.
+    ///
+    /// This is a linear-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let a: RotatedArraySet<_> = vec![1, 2, 3].into_iter().collect();
+    /// let b: RotatedArraySet<_> = vec![2, 3, 4].into_iter().collect();
+    ///
+    /// let result = &a & &b;
+    /// let result_vec: Vec<_> = result.into_iter().collect();
+    /// assert_eq!(result_vec, [2, 3]);
+    /// ```
+    fn bitand(self, rhs: &RotatedArraySet<T>) -> RotatedArraySet<T> {
+        RotatedArraySet::from_sorted_dedup_iter(self.intersection(rhs).cloned())
+    }
+}
+
+impl<T> BitOr<&RotatedArraySet<T>> for &RotatedArraySet<T>
 where
-    T: Ord + Copy + Default + Debug + Hash,
+    T: Ord + Clone,
 {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        for i in 0..self.len() {
-            self.select(i).hash(state);
-        }
+    type Output = RotatedArraySet<T>;
+
+    /// Returns the union of `self` and `rhs` as a new `RotatedArraySet<T>`.
+    ///
+    /// This is a linear-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let a: RotatedArraySet<_> = vec![1, 2, 3].into_iter().collect();
+    /// let b: RotatedArraySet<_> = vec![3, 4, 5].into_iter().collect();
+    ///
+    /// let result = &a | &b;
+    /// let result_vec: Vec<_> = result.into_iter().collect();
+    /// assert_eq!(result_vec, [1, 2, 3, 4, 5]);
+    /// ```
+    fn bitor(self, rhs: &RotatedArraySet<T>) -> RotatedArraySet<T> {
+        RotatedArraySet::from_sorted_dedup_iter(self.union(rhs).cloned())
     }
 }
 
 impl<'a, T> Iterator for Iter<'a, T>
 where
-    T: Ord + Copy + Default + Debug,
+    T: Ord,
 {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.len() == 0 || self.next_index > self.next_rev_index {
-            None
-        } else {
-            let current = self.range.at(self.next_index);
-            self.next_index += 1;
-            debug_assert!(self.assert_invariants());
-            current
+        loop {
+            if let Some((first, rest)) = self.front.split_first() {
+                self.front = rest;
+                return Some(first);
+            }
+            match self.chunks.next() {
+                Some(chunk) => self.front = chunk,
+                None => {
+                    let (first, rest) = self.back.split_first()?;
+                    self.back = rest;
+                    return Some(first);
+                }
+            }
         }
     }
 
-    fn nth(&mut self, n: usize) -> Option<Self::Item> {
-        self.next_index = min(self.next_index + n, self.len());
-        let ret = if self.len() == 0 || self.next_index > self.next_rev_index {
-            None
-        } else {
-            let nth = self.range.at(self.next_index);
-            self.next_index += 1;
-            nth
-        };
-        debug_assert!(self.assert_invariants());
-        ret
+    fn nth(&mut self, mut n: usize) -> Option<Self::Item> {
+        if n < self.front.len() {
+            self.front = &self.front[n..];
+            return self.next();
+        }
+        n -= self.front.len();
+        self.front = &[];
+        let skipped = min(n, self.chunks.remaining());
+        self.chunks.skip_front(skipped);
+        n -= skipped;
+        if n > 0 {
+            self.back = &self.back[min(n, self.back.len())..];
+        }
+        self.next()
     }
 
     fn count(self) -> usize {
-        self.len() - self.next_index
+        self.len()
     }
 
-    fn last(self) -> Option<Self::Item> {
-        if self.len() == 0 {
-            None
-        } else {
-            self.range.at(self.len() - 1)
-        }
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
     }
 
-    fn max(self) -> Option<Self::Item> {
-        if self.len() == 0 {
-            None
-        } else {
-            self.range.at(self.len() - 1)
-        }
+    fn max(mut self) -> Option<Self::Item> {
+        self.next_back()
     }
 
-    fn min(self) -> Option<Self::Item> {
-        self.range.at(0)
+    fn min(mut self) -> Option<Self::Item> {
+        self.next()
     }
 
     // FIXME: uncomment when Iterator::is_sorted is stabilized
@@ -1454,68 +3214,134 @@ where
     // }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining_count = self.len() - self.next_index;
+        let remaining_count = self.len();
         (remaining_count, Some(remaining_count))
     }
 }
 
 impl<'a, T> DoubleEndedIterator for Iter<'a, T>
 where
-    T: Ord + Copy + Default + Debug,
+    T: Ord,
 {
     fn next_back(&mut self) -> Option<Self::Item> {
-        if self.len() == 0 || self.next_rev_index < self.next_index {
-            None
-        } else {
-            let current = self.range.at(self.next_rev_index);
-            // We can't decrement next_rev_index past 0, so we cheat and move next_index
-            // ahead instead. That works since next() must return None once next_rev_index
-            // has crossed next_index.
-            if self.next_rev_index == 0 {
-                self.next_index += 1;
-            } else {
-                self.next_rev_index -= 1;
+        loop {
+            if let Some((last, rest)) = self.back.split_last() {
+                self.back = rest;
+                return Some(last);
+            }
+            match self.chunks.next_back() {
+                Some(chunk) => self.back = chunk,
+                None => {
+                    let (last, rest) = self.front.split_last()?;
+                    self.front = rest;
+                    return Some(last);
+                }
             }
-            debug_assert!(self.assert_invariants());
-            current
         }
     }
 
-    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
-        self.next_rev_index = self.next_rev_index.saturating_sub(n);
-        let ret = if self.len() == 0 || self.next_rev_index < self.next_index {
-            None
-        } else {
-            let nth = self.range.at(self.next_rev_index);
-            // We can't decrement next_rev_index past 0, so we cheat and move next_index
-            // ahead instead. That works since next() must return None once next_rev_index
-            // has crossed next_index.
-            if self.next_rev_index == 0 {
-                self.next_index += 1;
-            } else {
-                self.next_rev_index -= 1;
-            }
-            nth
-        };
-        debug_assert!(self.assert_invariants());
-        ret
+    fn nth_back(&mut self, mut n: usize) -> Option<Self::Item> {
+        if n < self.back.len() {
+            self.back = &self.back[..self.back.len() - n];
+            return self.next_back();
+        }
+        n -= self.back.len();
+        self.back = &[];
+        let skipped = min(n, self.chunks.remaining());
+        self.chunks.skip_back(skipped);
+        n -= skipped;
+        if n > 0 {
+            self.front = &self.front[..self.front.len() - min(n, self.front.len())];
+        }
+        self.next_back()
     }
 }
 
 impl<T> ExactSizeIterator for Iter<'_, T>
 where
-    T: Ord + Copy + Default + Debug,
+    T: Ord,
 {
     fn len(&self) -> usize {
-        self.range.len()
+        self.front.len() + self.chunks.remaining() + self.back.len()
+    }
+}
+
+impl<T> FusedIterator for Iter<'_, T> where T: Ord {}
+
+impl<'a, T> Iterator for Chunks<'a, T>
+where
+    T: Ord,
+{
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front_index >= self.back_index {
+            return None;
+        }
+        let SubarrayBounds { idx, start, len } = self.front_subarray;
+        let pivot_offset = self.container.min_indexes[idx];
+        // the logical offset within the subarray where the run after the pivot ends
+        let wrap_offset = len - pivot_offset;
+        let offset = self.front_index - start;
+        let (raw_start, end_index) = if offset < wrap_offset {
+            (
+                start + pivot_offset + offset,
+                min(start + wrap_offset, self.back_index),
+            )
+        } else {
+            (
+                start + offset - wrap_offset,
+                min(start + len, self.back_index),
+            )
+        };
+        let chunk = &self.container.data[raw_start..raw_start + (end_index - self.front_index)];
+        self.front_index = end_index;
+        if self.front_index == start + len && self.front_index < self.back_index {
+            self.front_subarray = self.container.next_subarray_bounds(self.front_subarray);
+        }
+        Some(chunk)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // each remaining element is in a chunk of its own at worst
+        let remaining_count = self.remaining();
+        (min(remaining_count, 1), Some(remaining_count))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Chunks<'a, T>
+where
+    T: Ord,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front_index >= self.back_index {
+            return None;
+        }
+        let SubarrayBounds { idx, start, len } = self.back_subarray;
+        let pivot_offset = self.container.min_indexes[idx];
+        let wrap_offset = len - pivot_offset;
+        let end_offset = self.back_index - start;
+        let (raw_start, start_index) = if end_offset > wrap_offset {
+            let start_index = max(start + wrap_offset, self.front_index);
+            (start_index - wrap_offset, start_index)
+        } else {
+            let start_index = max(start, self.front_index);
+            (start + pivot_offset + (start_index - start), start_index)
+        };
+        let chunk = &self.container.data[raw_start..raw_start + (self.back_index - start_index)];
+        self.back_index = start_index;
+        if self.back_index == start && self.front_index < self.back_index {
+            self.back_subarray = self.container.prev_subarray_bounds(self.back_subarray);
+        }
+        Some(chunk)
     }
 }
 
-impl<T> FusedIterator for Iter<'_, T> where T: Ord + Copy + Default + Debug {}
+impl<T> FusedIterator for Chunks<'_, T> where T: Ord {}
 
 impl<'a, T> IntoIterator for &'a RotatedArraySet<T>
 where
-    T: Ord + Copy + Default + Debug,
+    T: Ord,
 {
     type Item = &'a T;
     type IntoIter = Iter<'a, T>;
@@ -1527,143 +3353,244 @@ where
 
 impl<T> IntoIterator for RotatedArraySet<T>
 where
-    T: Ord + Copy + Default + Debug,
+    T: Ord,
 {
     type Item = T;
     type IntoIter = IntoIter<T>;
 
     fn into_iter(self) -> Self::IntoIter {
+        let vec: Vec<T> = self.into();
         IntoIter {
-            vec: self.into(),
-            next_index: 0,
+            iter: vec.into_iter(),
         }
     }
 }
 
-impl<'a, T> Iterator for IntoIter<T>
+impl<T> Iterator for IntoIter<T>
 where
-    T: Ord + Copy + Default + Debug,
+    T: Ord,
 {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.next_index == self.vec.len() {
-            None
-        } else {
-            let current = self.vec[self.next_index];
-            self.next_index += 1;
-            debug_assert!(self.next_index <= self.vec.len());
-            Some(current)
-        }
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T>
+where
+    T: Ord,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
     }
 }
 
-/// From https://doc.rust-lang.org/src/alloc/collections/btree/set.rs.html
-/// Compares `x` and `y`, but return `short` if x is None and `long` if y is None
-fn cmp_opt<T: Ord>(x: Option<&T>, y: Option<&T>, short: Ordering, long: Ordering) -> Ordering {
-    match (x, y) {
-        (None, _) => short,
-        (_, None) => long,
-        (Some(x1), Some(y1)) => x1.cmp(y1),
+impl<T> ExactSizeIterator for IntoIter<T> where T: Ord {}
+
+impl<T> FusedIterator for IntoIter<T> where T: Ord {}
+
+impl<'a, T> Difference<'a, T>
+where
+    T: Ord,
+{
+    fn new(a: Layout<'a, T>, b: Layout<'a, T>) -> Difference<'a, T> {
+        Difference {
+            a: Galloping::new(Range::new(a)),
+            b: Galloping::new(Range::new(b)),
+            a_run: 0,
+        }
     }
 }
 
 impl<'a, T> Iterator for Difference<'a, T>
 where
-    T: Ord + Copy + Default + Debug,
+    T: Ord,
 {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<&'a T> {
-        // Just use a simple lookup from `self_iter` to `other_set` for now,
-        // later add a proper linear merge for size ratios close to 1 if benchmarks warrant.
-        // (A point lookup has much better worst-case performance than linear merge.)
-        // NB: For a single algorithm optimal over all size ratios, see
-        // "A simple algorithm for merging two disjoint linearly-ordered sets".
         loop {
-            let self_next = self.self_iter.next()?;
-            if !self.other_set.contains(&self_next) {
-                return Some(self_next);
+            if self.a_run > 0 {
+                self.a_run -= 1;
+                return self.a.next();
+            }
+            let a_next = self.a.peek()?;
+            self.b.skip_less(a_next);
+            match self.b.peek() {
+                None => self.a_run = self.a.len(),
+                Some(b_next) if b_next == a_next => {
+                    self.a.next();
+                    self.b.next();
+                }
+                Some(b_next) => self.a_run = self.a.count_less(b_next),
             }
         }
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let (self_len, other_len) = (self.self_iter.len(), self.other_set.len());
-        (self_len.saturating_sub(other_len), Some(self_len))
+        let (a_len, b_len) = (self.a.len(), self.b.len());
+        (max(a_len.saturating_sub(b_len), self.a_run), Some(a_len))
     }
 }
 
-impl<T> FusedIterator for Difference<'_, T> where T: Ord + Copy + Default + Debug {}
+impl<T> FusedIterator for Difference<'_, T> where T: Ord {}
+
+impl<'a, T> SymmetricDifference<'a, T>
+where
+    T: Ord,
+{
+    fn new(a: Layout<'a, T>, b: Layout<'a, T>) -> SymmetricDifference<'a, T> {
+        SymmetricDifference {
+            a: Galloping::new(Range::new(a)),
+            b: Galloping::new(Range::new(b)),
+            a_run: 0,
+            b_run: 0,
+        }
+    }
+}
 
 impl<'a, T> Iterator for SymmetricDifference<'a, T>
 where
-    T: Ord + Copy + Default + Debug,
+    T: Ord,
 {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<&'a T> {
         loop {
-            match cmp_opt(self.a.peek(), self.b.peek(), Greater, Less) {
-                Less => return self.a.next(),
-                Equal => {
-                    self.a.next();
-                    self.b.next();
-                }
-                Greater => return self.b.next(),
+            if self.a_run > 0 {
+                self.a_run -= 1;
+                return self.a.next();
+            }
+            if self.b_run > 0 {
+                self.b_run -= 1;
+                return self.b.next();
+            }
+            match (self.a.peek(), self.b.peek()) {
+                (None, None) => return None,
+                (Some(_), None) => self.a_run = self.a.len(),
+                (None, Some(_)) => self.b_run = self.b.len(),
+                (Some(a_next), Some(b_next)) => match a_next.cmp(b_next) {
+                    Less => self.a_run = self.a.count_less(b_next),
+                    Equal => {
+                        self.a.next();
+                        self.b.next();
+                    }
+                    Greater => self.b_run = self.b.count_less(a_next),
+                },
             }
         }
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (0, Some(self.a.len() + self.b.len()))
+        (self.a_run + self.b_run, Some(self.a.len() + self.b.len()))
     }
 }
 
-impl<T> FusedIterator for SymmetricDifference<'_, T> where T: Ord + Copy + Default + Debug {}
+impl<T> FusedIterator for SymmetricDifference<'_, T> where T: Ord {}
+
+impl<'a, T> Intersection<'a, T>
+where
+    T: Ord,
+{
+    fn new(a: Layout<'a, T>, b: Layout<'a, T>) -> Intersection<'a, T> {
+        // Values outside the range spanned by the other set can't be in the intersection.
+        let span = |set: Layout<'a, T>, other: Layout<'a, T>| match (other.first(), other.last()) {
+            (Some(first), Some(last)) => set.get_range((Included(first), Included(last))),
+            _ => Range::with_bounds(set, 0, 0),
+        };
+        Intersection {
+            a: Galloping::new(span(a, b)),
+            b: Galloping::new(span(b, a)),
+        }
+    }
+}
 
 impl<'a, T> Iterator for Intersection<'a, T>
 where
-    T: Ord + Copy + Default + Debug,
+    T: Ord,
 {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<&'a T> {
-        // Just use a simple lookup from `self_iter` to `other_set` for now,
-        // later add a proper linear merge for size ratios close to 1 if benchmarks warrant.
-        // (A point lookup has much better worst-case performance than linear merge.)
-        // NB: For a single algorithm optimal over all size ratios, see
-        // "A simple algorithm for merging two disjoint linearly-ordered sets".
+        // Alternately gallop each side forward to the next value of the other side,
+        // so that long stretches of either side without a match are skipped.
         loop {
-            let small_next = self.small_iter.next()?;
-            if self.large_set.contains(&small_next) {
-                return Some(small_next);
+            let a_next = self.a.peek()?;
+            self.b.skip_less(a_next);
+            let b_next = self.b.peek()?;
+            if a_next == b_next {
+                self.b.next();
+                return self.a.next();
             }
+            self.a.skip_less(b_next);
         }
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let min_len = self.small_iter.len();
-        (0, Some(min_len))
+        (0, Some(min(self.a.len(), self.b.len())))
     }
 }
 
-impl<T> FusedIterator for Intersection<'_, T> where T: Ord + Copy + Default + Debug {}
+impl<T> FusedIterator for Intersection<'_, T> where T: Ord {}
+
+impl<'a, T> Union<'a, T>
+where
+    T: Ord,
+{
+    fn new(a: Layout<'a, T>, b: Layout<'a, T>) -> Union<'a, T> {
+        Union {
+            a: Galloping::new(Range::new(a)),
+            b: Galloping::new(Range::new(b)),
+            a_run: 0,
+            b_run: 0,
+        }
+    }
+}
 
 impl<'a, T> Iterator for Union<'a, T>
 where
-    T: Ord + Copy + Default + Debug,
+    T: Ord,
 {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<&'a T> {
-        match cmp_opt(self.a.peek(), self.b.peek(), Greater, Less) {
-            Less => self.a.next(),
-            Equal => {
-                self.b.next();
+        if self.a_run > 0 {
+            self.a_run -= 1;
+            return self.a.next();
+        }
+        if self.b_run > 0 {
+            self.b_run -= 1;
+            return self.b.next();
+        }
+        match (self.a.peek(), self.b.peek()) {
+            (None, None) => None,
+            (Some(_), None) => {
+                self.a_run = self.a.len() - 1;
                 self.a.next()
             }
-            Greater => self.b.next(),
+            (None, Some(_)) => {
+                self.b_run = self.b.len() - 1;
+                self.b.next()
+            }
+            (Some(a_next), Some(b_next)) => match a_next.cmp(b_next) {
+                Less => {
+                    self.a_run = self.a.count_less(b_next) - 1;
+                    self.a.next()
+                }
+                Equal => {
+                    self.b.next();
+                    self.a.next()
+                }
+                Greater => {
+                    self.b_run = self.b.count_less(a_next) - 1;
+                    self.b.next()
+                }
+            },
         }
     }
 
@@ -1674,17 +3601,93 @@ where
     }
 }
 
-impl<T> FusedIterator for Union<'_, T> where T: Ord + Copy + Default + Debug {}
+impl<T> FusedIterator for Union<'_, T> where T: Ord {}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Drain<'_, T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.iter.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+
+impl<T> FusedIterator for Drain<'_, T> {}
+
+impl<T> Drop for Drain<'_, T> {
+    fn drop(&mut self) {
+        // every subarray from the start of the range onward is in sorted order
+        self.min_indexes.resize(self.subarray_count, 0);
+    }
+}
+
+impl<T, F> Iterator for ExtractIf<'_, T, F>
+where
+    T: Ord,
+    F: FnMut(&T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while self.remaining > 0 {
+            self.remaining -= 1;
+            // retain the value before calling the predicate, in case it panics
+            let value = self.deque.pop_front().unwrap();
+            self.deque.push_back(value);
+            if (self.pred)(self.deque.back().unwrap()) {
+                return self.deque.pop_back();
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining))
+    }
+}
+
+impl<T, F> FusedIterator for ExtractIf<'_, T, F>
+where
+    T: Ord,
+    F: FnMut(&T) -> bool,
+{
+}
+
+impl<T, F> Drop for ExtractIf<'_, T, F>
+where
+    T: Ord,
+    F: FnMut(&T) -> bool,
+{
+    fn drop(&mut self) {
+        // the unvisited values are at the front, and are all greater than the retained values
+        self.deque.rotate_left(self.remaining);
+        self.set.data = Vec::from(mem::take(&mut self.deque));
+        self.set.init_min_indexes();
+        debug_assert!(self.set.layout().assert_invariants());
+    }
+}
 
-impl<'a, T> From<&'a [T]> for RotatedArraySet<T>
+impl<T> From<&[T]> for RotatedArraySet<T>
 where
-    T: Ord + Copy + Default + Debug,
+    T: Ord + Clone,
 {
+    /// Builds a set from a slice, cloning its elements. If an element occurs more than once,
+    /// the set keeps its first occurrence, just as if the elements had been inserted in order.
     fn from(slice: &[T]) -> Self {
         let mut this = RotatedArraySet {
             data: slice.to_vec(),
             min_indexes: Vec::new(),
-            min_data: Vec::new(),
         };
         this.init();
         this
@@ -1693,104 +3696,321 @@ where
 
 impl<T> From<Vec<T>> for RotatedArraySet<T>
 where
-    T: Ord + Copy + Default + Debug,
+    T: Ord,
 {
+    /// Builds a set from a vector, reusing its allocation. If an element occurs more than once,
+    /// the set keeps its first occurrence, just as if the elements had been inserted in order.
     fn from(vec: Vec<T>) -> Self {
         let mut this = RotatedArraySet {
             data: vec,
             min_indexes: Vec::new(),
-            min_data: Vec::new(),
         };
         this.init();
         this
     }
 }
 
-impl<T> Into<Vec<T>> for RotatedArraySet<T>
+impl<T> From<RotatedArraySet<T>> for Vec<T>
 where
-    T: Ord + Copy + Default + Debug,
+    T: Ord,
 {
-    fn into(mut self) -> Vec<T> {
-        // sort the data array in-place and steal it from self
-        for (i, &pivot_offset) in self.min_indexes.iter().enumerate() {
-            let subarray_start_idx = Self::get_array_idx_from_subarray_idx(i);
-            let subarray_len = if i == self.min_indexes.len() - 1 {
-                self.data.len() - subarray_start_idx
-            } else {
-                i + 1
-            };
-            let subarray_end_idx = subarray_start_idx + subarray_len;
-            let subarray = &mut self.data[subarray_start_idx..subarray_end_idx];
-            // sort subarray in-place
-            subarray.rotate_left(pivot_offset);
-        }
-        // steal data array
-        self.data
+    fn from(mut set: RotatedArraySet<T>) -> Vec<T> {
+        // sort the data array in-place and steal it from the set
+        set.sort_subarrays(0, set.min_indexes.len());
+        set.data
     }
 }
 
 impl<T> FromIterator<T> for RotatedArraySet<T>
 where
-    T: Ord + Copy + Default + Debug,
+    T: Ord,
 {
+    /// Builds a set from an iterator. If an element occurs more than once,
+    /// the set keeps its first occurrence, just as if the elements had been inserted in order.
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
         let mut this = RotatedArraySet {
-            data: Vec::from_iter(iter.into_iter()),
+            data: Vec::from_iter(iter),
             min_indexes: Vec::new(),
-            min_data: Vec::new(),
         };
         this.init();
         this
     }
 }
 
+impl<T> Extend<T> for RotatedArraySet<T>
+where
+    T: Ord,
+{
+    /// Adds all values from an iterator. Values already present in the set are not replaced,
+    /// and only the first of several equal values from the iterator is added, just as if the
+    /// values had been inserted in order.
+    ///
+    /// Small batches are inserted one value at a time, and large batches are sorted and
+    /// merged into the set in a single pass.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut batch: Vec<T> = iter.into_iter().collect();
+        // a stable sort preserves the insertion order of duplicates
+        batch.sort();
+        self.insert_batch(batch);
+    }
+}
+
+impl<'a, T> Extend<&'a T> for RotatedArraySet<T>
+where
+    T: 'a + Ord + Copy,
+{
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned());
+    }
+}
+
 impl<T> Default for RotatedArraySet<T>
 where
-    T: Ord + Copy + Default + Debug,
+    T: Ord,
 {
     fn default() -> RotatedArraySet<T> {
         RotatedArraySet::new()
     }
 }
 
+// Returns the rank of the value at quantile `q` of a sequence of the given length, if any.
+fn quantile_rank(q: f64, len: usize) -> Option<usize> {
+    assert!((0.0..=1.0).contains(&q), "quantile must be in [0, 1]");
+    let last = len.checked_sub(1)?;
+    Some(min((q * last as f64) as usize, last))
+}
+
+// Returns the percentage of values less than a given value in a sequence of the given length,
+// counting values equal to it as half.
+fn percentile_rank(less: usize, equal: usize, len: usize) -> f64 {
+    if len == 0 {
+        0.0
+    } else {
+        (less as f64 + equal as f64 / 2.0) * 100.0 / len as f64
+    }
+}
+
+// `TryReserveError` can't be constructed directly, so obtain the error `Vec` reports
+// for a capacity that can never be allocated.
+fn capacity_overflow() -> TryReserveError {
+    Vec::<u8>::new().try_reserve(usize::MAX).unwrap_err()
+}
+
+// Sorts a slice in place by a stable sort that never allocates, which takes `O(n lg n)`
+// comparisons and `O(n lg² n)` swaps: sorted blocks are built by insertion sort and then
+// merged pairwise by `sym_merge`.
+fn stable_sort_in_place<T: Ord>(v: &mut [T]) {
+    const BLOCK_LEN: usize = 20;
+    for block in v.chunks_mut(BLOCK_LEN) {
+        for i in 1..block.len() {
+            let mut j = i;
+            while j > 0 && block[j] < block[j - 1] {
+                block.swap(j, j - 1);
+                j -= 1;
+            }
+        }
+    }
+    let mut block_len = BLOCK_LEN;
+    while block_len < v.len() {
+        for pair in v.chunks_mut(2 * block_len) {
+            if pair.len() > block_len {
+                sym_merge(pair, block_len);
+            }
+        }
+        block_len *= 2;
+    }
+}
+
+// Stably merges the sorted runs `v[..mid]` and `v[mid..]` in place, using the SymMerge
+// algorithm of Kim and Kutzner, "Stable Minimum Storage Merging by Symmetric Comparisons".
+fn sym_merge<T: Ord>(v: &mut [T], mid: usize) {
+    let len = v.len();
+    if mid == 0 || mid == len {
+        return;
+    }
+    if mid == 1 {
+        // move the first value after all smaller values in the right run
+        let i = mid + v[mid..].partition_point(|x| *x < v[0]);
+        v[..i].rotate_left(1);
+        return;
+    }
+    if len - mid == 1 {
+        // move the last value before all greater values in the left run
+        let i = v[..mid].partition_point(|x| *x <= v[mid]);
+        v[i..].rotate_right(1);
+        return;
+    }
+    let half = len / 2;
+    let n = half + mid;
+    let (mut start, mut end) = if mid > half {
+        (n - len, half)
+    } else {
+        (0, mid)
+    };
+    let p = n - 1;
+    while start < end {
+        let c = (start + end) / 2;
+        if v[p - c] < v[c] {
+            end = c;
+        } else {
+            start = c + 1;
+        }
+    }
+    let end = n - start;
+    if start < mid && mid < end {
+        v[start..end].rotate_left(mid - start);
+    }
+    sym_merge(&mut v[..half], start);
+    sym_merge(&mut v[half..], end - half);
+}
+
+const fn get_subarray_idx_from_array_idx(idx: usize) -> usize {
+    if idx == 0 {
+        0
+    } else {
+        integer_sum_inverse(idx)
+    }
+}
+
+fn get_array_idx_from_subarray_idx(idx: usize) -> usize {
+    if idx == 0 {
+        0
+    } else {
+        integer_sum(idx)
+    }
+}
+
+const fn integer_sum(n: usize) -> usize {
+    match checked_integer_sum(n) {
+        Some(sum) => sum,
+        None => panic!("integer sum overflow"),
+    }
+}
+
+const fn checked_integer_sum(n: usize) -> Option<usize> {
+    // I learned this from a 10-year-old named Gauss
+    // (halving the even factor first keeps the product from overflowing
+    // whenever the sum itself fits in a `usize`)
+    if n % 2 == 0 {
+        (n / 2).checked_mul(n + 1)
+    } else {
+        n.checked_mul(n.div_ceil(2))
+    }
+}
+
+// Returns the largest `x` such that `integer_sum(x) <= n`.
+const fn integer_sum_inverse(n: usize) -> usize {
+    // y = (x * (x + 1)) / 2
+    // x = (sqrt(8 * y + 1) - 1) / 2
+    //
+    // `8 * y + 1` may overflow, so start from `r = isqrt(2 * y)` instead, which is either `x`
+    // or `x + 1`. Since `2 * y` may overflow too, it is computed from `s = isqrt(y / 2)`:
+    // `r` is either `2 * s` or `2 * s + 1`.
+    let half = n / 2;
+    let s = integer_sqrt(half);
+    let r = if s * (s + 1) < half + n % 2 {
+        2 * s + 1
+    } else {
+        2 * s
+    };
+    // `integer_sum(r)` can only overflow if it exceeds `n`
+    match checked_integer_sum(r) {
+        Some(sum) if sum <= n => r,
+        _ => r - 1,
+    }
+}
+
+// Returns the largest `x` such that `x * x <= n`.
+const fn integer_sqrt(n: usize) -> usize {
+    if n < 2 {
+        return n;
+    }
+    // Newton's method, starting from a power of two no smaller than the root,
+    // decreases monotonically until it reaches the root
+    let bits = (core::mem::size_of::<usize>() * 8) as u32 - n.leading_zeros();
+    let mut x = 1 << bits.div_ceil(2);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::RotatedArraySet;
+    use super::{integer_sqrt, integer_sum, integer_sum_inverse, RotatedArraySet};
     use proptest::prelude::*;
 
     fn assert_sum_invariant(n: usize) -> Result<(), TestCaseError> {
-        let sum = RotatedArraySet::<u8>::integer_sum(n);
-        let inv = RotatedArraySet::<u8>::integer_sum_inverse(sum);
+        let sum = integer_sum(n);
+        let inv = integer_sum_inverse(sum);
         prop_assert_eq!(n, inv);
         Ok(())
     }
 
     fn assert_inverse_invariant(n: usize) -> Result<(), TestCaseError> {
-        let inv = RotatedArraySet::<u8>::integer_sum_inverse(n);
-        let sum_lower = RotatedArraySet::<u8>::integer_sum(inv);
-        let sum_upper = RotatedArraySet::<u8>::integer_sum(inv + 1);
+        let inv = integer_sum_inverse(n);
+        let sum_lower = integer_sum(inv);
+        // the next sum may not fit in a `usize`, in which case it certainly exceeds `n`
+        let sum_upper = sum_lower.checked_add(inv + 1);
         prop_assert!(sum_lower <= n);
-        prop_assert!(n < sum_upper);
+        prop_assert!(sum_upper.map_or(true, |sum_upper| n < sum_upper));
         Ok(())
     }
 
     prop_compose! {
         /// generates integer_sum(?) - 1, integer_sum(?), and integer_sum(?) + 1.
-        fn inverse_boundary()(n in 0..(((usize::MAX/2) as f64).sqrt() as usize), d in 0usize..3) -> usize {
-            RotatedArraySet::<u8>::integer_sum(n)
+        fn inverse_boundary()(n in 0..=integer_sum_inverse(usize::MAX), d in 0usize..3) -> usize {
+            integer_sum(n)
                 .wrapping_add(d)
                 .wrapping_sub(1)
         }
     }
 
+    #[test]
+    fn inverse_at_usize_max() {
+        let inv = integer_sum_inverse(usize::MAX);
+        assert!(integer_sum(inv).checked_add(inv + 1).is_none());
+        assert_eq!(
+            integer_sqrt(usize::MAX),
+            (1 << (usize::MAX.count_ones() / 2)) - 1
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn with_capacity_overflow() {
+        RotatedArraySet::<()>::with_capacity(usize::MAX);
+    }
+
+    #[test]
+    fn try_with_capacity_overflow() {
+        assert!(RotatedArraySet::<()>::try_with_capacity(usize::MAX).is_err());
+        assert!(RotatedArraySet::<u64>::try_with_capacity(usize::MAX / 16).is_err());
+        let set = RotatedArraySet::<u64>::try_with_capacity(100).unwrap();
+        assert!(set.data.capacity() >= 100 && set.min_indexes.capacity() >= 14);
+    }
+
+    #[test]
+    fn try_reserve_overflow() {
+        let mut set: RotatedArraySet<u64> = (0..100).collect();
+        assert!(set.try_reserve(usize::MAX - 50).is_err());
+        assert!(set.try_reserve(usize::MAX / 16).is_err());
+        assert!(set.iter().eq((0..100).collect::<Vec<_>>().iter()));
+        assert_eq!(set.try_insert(100), Ok(true));
+        assert!(set.layout().assert_invariants());
+    }
+
     proptest! {
         #[test]
-        fn take_sum_and_inverse(n in 0..(((usize::MAX/2) as f64).sqrt() as usize)) {
+        fn take_sum_and_inverse(n in 0..=integer_sum_inverse(usize::MAX)) {
             assert_sum_invariant(n)?
         }
 
         #[test]
-        fn take_inverse_and_sum(n in 0..usize::MAX/2) {
+        fn take_inverse_and_sum(n in any::<usize>()) {
             assert_inverse_invariant(n)?
         }
 
@@ -1798,5 +4018,12 @@ mod test {
         fn take_inverse_and_sum_on_inverse_boundary(n in inverse_boundary()) {
             assert_inverse_invariant(n)?
         }
+
+        #[test]
+        fn take_sqrt(n in any::<usize>()) {
+            let root = integer_sqrt(n);
+            prop_assert!(root * root <= n);
+            prop_assert!((root + 1).checked_mul(root + 1).map_or(true, |square| square > n));
+        }
     }
 }
@@ -0,0 +1,1156 @@
+//! An ordered map based on a 2-level rotated array.
+//!
+//! The map stores its entries as key-value pairs in a [`RotatedArraySet`] ordered by key,
+//! so it has exactly the same layout and memory overhead as the set.
+//!
+//! [`RotatedArraySet`]: ../struct.RotatedArraySet.html
+
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::iter::{DoubleEndedIterator, ExactSizeIterator, FromIterator, FusedIterator};
+use core::mem;
+use core::ops::RangeBounds;
+
+use alloc::vec::Vec;
+
+use crate::{get_subarray_idx_from_array_idx, RetainGuard, RotatedArraySet};
+
+// A key-value pair, ordered by key alone.
+#[derive(Debug, Clone)]
+struct Pair<K, V> {
+    key: K,
+    value: V,
+}
+
+impl<K, V> PartialEq for Pair<K, V>
+where
+    K: Ord,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<K, V> Eq for Pair<K, V> where K: Ord {}
+
+impl<K, V> PartialOrd for Pair<K, V>
+where
+    K: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K, V> Ord for Pair<K, V>
+where
+    K: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+/// An ordered map based on a 2-level rotated array.
+///
+/// # Examples
+///
+/// ```
+/// use rotated_array_set::RotatedArrayMap;
+///
+/// // Type inference lets us omit an explicit type signature (which
+/// // would be `RotatedArrayMap<&str, &str>` in this example).
+/// let mut movie_reviews = RotatedArrayMap::new();
+///
+/// // review some movies.
+/// movie_reviews.insert("Office Space", "Deals with real issues in the workplace.");
+/// movie_reviews.insert("Pulp Fiction", "Masterpiece.");
+/// movie_reviews.insert("The Godfather", "Very enjoyable.");
+///
+/// // check for a specific one.
+/// if !movie_reviews.contains_key("Les Misérables") {
+///     println!("We've got {} reviews, but Les Misérables ain't one.", movie_reviews.len());
+/// }
+///
+/// // oops, this review has a lot of spelling mistakes, let's delete it.
+/// movie_reviews.remove("The Godfather");
+///
+/// // look up the value associated with some key, or its rank.
+/// assert_eq!(movie_reviews.get("Pulp Fiction"), Some(&"Masterpiece."));
+/// assert_eq!(movie_reviews.rank("Pulp Fiction"), Ok(1));
+///
+/// // iterate over everything.
+/// for (movie, review) in &movie_reviews {
+///     println!("{}: \"{}\"", movie, review);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RotatedArrayMap<K, V> {
+    set: RotatedArraySet<Pair<K, V>>,
+}
+
+/// An iterator over the entries of a `RotatedArrayMap`.
+///
+/// This `struct` is created by the [`iter`] and [`range`] methods on [`RotatedArrayMap`].
+/// See their documentation for more.
+///
+/// [`RotatedArrayMap`]: struct.RotatedArrayMap.html
+/// [`iter`]: struct.RotatedArrayMap.html#method.iter
+/// [`range`]: struct.RotatedArrayMap.html#method.range
+#[derive(Debug)]
+pub struct Iter<'a, K: 'a, V: 'a> {
+    iter: crate::Iter<'a, Pair<K, V>>,
+}
+
+/// A mutable iterator over the entries of a `RotatedArrayMap`.
+///
+/// This `struct` is created by the [`iter_mut`] and [`range_mut`] methods on [`RotatedArrayMap`].
+/// See their documentation for more.
+///
+/// [`RotatedArrayMap`]: struct.RotatedArrayMap.html
+/// [`iter_mut`]: struct.RotatedArrayMap.html#method.iter_mut
+/// [`range_mut`]: struct.RotatedArrayMap.html#method.range_mut
+#[derive(Debug)]
+pub struct IterMut<'a, K: 'a, V: 'a> {
+    iter: core::slice::IterMut<'a, Pair<K, V>>,
+}
+
+/// An owning iterator over the entries of a `RotatedArrayMap`.
+///
+/// This `struct` is created by the [`into_iter`] method on [`RotatedArrayMap`]
+/// (provided by the `IntoIterator` trait). See its documentation for more.
+///
+/// [`RotatedArrayMap`]: struct.RotatedArrayMap.html
+/// [`into_iter`]: struct.RotatedArrayMap.html#method.into_iter
+#[derive(Debug)]
+pub struct IntoIter<K, V> {
+    iter: crate::IntoIter<Pair<K, V>>,
+}
+
+/// An iterator over the keys of a `RotatedArrayMap`.
+///
+/// This `struct` is created by the [`keys`] method on [`RotatedArrayMap`].
+/// See its documentation for more.
+///
+/// [`RotatedArrayMap`]: struct.RotatedArrayMap.html
+/// [`keys`]: struct.RotatedArrayMap.html#method.keys
+#[derive(Debug)]
+pub struct Keys<'a, K: 'a, V: 'a> {
+    inner: Iter<'a, K, V>,
+}
+
+/// An iterator over the values of a `RotatedArrayMap`.
+///
+/// This `struct` is created by the [`values`] method on [`RotatedArrayMap`].
+/// See its documentation for more.
+///
+/// [`RotatedArrayMap`]: struct.RotatedArrayMap.html
+/// [`values`]: struct.RotatedArrayMap.html#method.values
+#[derive(Debug)]
+pub struct Values<'a, K: 'a, V: 'a> {
+    inner: Iter<'a, K, V>,
+}
+
+/// A mutable iterator over the values of a `RotatedArrayMap`.
+///
+/// This `struct` is created by the [`values_mut`] method on [`RotatedArrayMap`].
+/// See its documentation for more.
+///
+/// [`RotatedArrayMap`]: struct.RotatedArrayMap.html
+/// [`values_mut`]: struct.RotatedArrayMap.html#method.values_mut
+#[derive(Debug)]
+pub struct ValuesMut<'a, K: 'a, V: 'a> {
+    inner: IterMut<'a, K, V>,
+}
+
+impl<K, V> RotatedArrayMap<K, V>
+where
+    K: Ord,
+{
+    /// Makes a new, empty `RotatedArrayMap` without any heap allocations.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut map = RotatedArrayMap::new();
+    ///
+    /// // entries can now be inserted into the empty map
+    /// map.insert(1, "a");
+    /// ```
+    pub fn new() -> Self {
+        RotatedArrayMap {
+            set: RotatedArraySet::new(),
+        }
+    }
+
+    /// Constructs a new, empty `RotatedArrayMap<K, V>` with the specified capacity.
+    ///
+    /// The map will be able to hold exactly `capacity` entries without
+    /// reallocating. If `capacity` is 0, the map will not allocate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut map = RotatedArrayMap::with_capacity(10);
+    /// assert_eq!(map.len(), 0);
+    /// map.insert(1, "a");
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        RotatedArrayMap {
+            set: RotatedArraySet::with_capacity(capacity),
+        }
+    }
+
+    /// Clears the map, removing all entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut a = RotatedArrayMap::new();
+    /// a.insert(1, "a");
+    /// a.clear();
+    /// assert!(a.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.set.clear();
+    }
+
+    /// Returns the number of entries in the map.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut a = RotatedArrayMap::new();
+    /// assert_eq!(a.len(), 0);
+    /// a.insert(1, "a");
+    /// assert_eq!(a.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    /// Returns `true` if the map contains no entries.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut a = RotatedArrayMap::new();
+    /// assert!(a.is_empty());
+    /// a.insert(1, "a");
+    /// assert!(!a.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    ///
+    /// The key may be any borrowed form of the map's key type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the key type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut map = RotatedArrayMap::new();
+    /// map.insert(1, "a");
+    /// assert_eq!(map.contains_key(&1), true);
+    /// assert_eq!(map.contains_key(&2), false);
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find_raw_index(key).is_ok()
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// The key may be any borrowed form of the map's key type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the key type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut map = RotatedArrayMap::new();
+    /// map.insert(1, "a");
+    /// assert_eq!(map.get(&1), Some(&"a"));
+    /// assert_eq!(map.get(&2), None);
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let raw_idx = self.find_raw_index(key).ok()?;
+        Some(&self.set.data[raw_idx].value)
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key.
+    ///
+    /// The supplied key may be any borrowed form of the map's key type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the key type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut map = RotatedArrayMap::new();
+    /// map.insert(1, "a");
+    /// assert_eq!(map.get_key_value(&1), Some((&1, &"a")));
+    /// assert_eq!(map.get_key_value(&2), None);
+    /// ```
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let raw_idx = self.find_raw_index(key).ok()?;
+        let pair = &self.set.data[raw_idx];
+        Some((&pair.key, &pair.value))
+    }
+
+    /// Returns a mutable reference to the value corresponding to the key.
+    ///
+    /// The key may be any borrowed form of the map's key type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the key type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut map = RotatedArrayMap::new();
+    /// map.insert(1, "a");
+    /// if let Some(x) = map.get_mut(&1) {
+    ///     *x = "b";
+    /// }
+    /// assert_eq!(map.get(&1), Some(&"b"));
+    /// ```
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let raw_idx = self.find_raw_index(key).ok()?;
+        Some(&mut self.set.data[raw_idx].value)
+    }
+
+    /// Returns the rank of the key in the map if it exists (as `Result::Ok`),
+    /// or the rank of its largest predecessor plus one, if it does not exist (as `Result::Err`).
+    ///
+    /// The key may be any borrowed form of the map's key type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the key type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let map: RotatedArrayMap<_, _> = vec![(1, "a"), (2, "b"), (3, "c")].into_iter().collect();
+    /// assert_eq!(map.rank(&1), Ok(0));
+    /// assert_eq!(map.rank(&4), Err(3));
+    /// ```
+    pub fn rank<Q>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.set.layout().rank_by(|pair| pair.key.borrow().cmp(key))
+    }
+
+    /// Returns the key-value pair, if any, with the given rank.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let map: RotatedArrayMap<_, _> = vec![(1, "a"), (2, "b"), (3, "c")].into_iter().collect();
+    /// assert_eq!(map.select(0), Some((&1, &"a")));
+    /// assert_eq!(map.select(3), None);
+    /// ```
+    pub fn select(&self, rank: usize) -> Option<(&K, &V)> {
+        let pair = self.set.select(rank)?;
+        Some((&pair.key, &pair.value))
+    }
+
+    /// Returns the key and a mutable reference to the value, if any, with the given rank.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut map: RotatedArrayMap<_, _> = vec![(1, "a"), (2, "b")].into_iter().collect();
+    /// if let Some((_, value)) = map.select_mut(1) {
+    ///     *value = "c";
+    /// }
+    /// assert_eq!(map.get(&2), Some(&"c"));
+    /// ```
+    pub fn select_mut(&mut self, rank: usize) -> Option<(&K, &mut V)> {
+        if rank >= self.len() {
+            return None;
+        }
+        let raw_idx = self.set.layout().get_raw_idx_from_logical_idx(rank);
+        let pair = &mut self.set.data[raw_idx];
+        Some((&pair.key, &mut pair.value))
+    }
+
+    /// Returns the first (i.e., smallest-keyed) key-value pair in the map, if any.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut map = RotatedArrayMap::new();
+    /// assert_eq!(map.first_key_value(), None);
+    /// map.insert(2, "b");
+    /// map.insert(1, "a");
+    /// assert_eq!(map.first_key_value(), Some((&1, &"a")));
+    /// ```
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        let pair = self.set.first()?;
+        Some((&pair.key, &pair.value))
+    }
+
+    /// Returns the last (i.e., largest-keyed) key-value pair in the map, if any.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut map = RotatedArrayMap::new();
+    /// map.insert(1, "a");
+    /// map.insert(2, "b");
+    /// assert_eq!(map.last_key_value(), Some((&2, &"b")));
+    /// ```
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        let pair = self.set.last()?;
+        Some((&pair.key, &pair.value))
+    }
+
+    /// Removes and returns the first (i.e., smallest-keyed) key-value pair in the map, if any.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut map: RotatedArrayMap<_, _> = vec![(1, "a"), (2, "b")].into_iter().collect();
+    /// assert_eq!(map.pop_first(), Some((1, "a")));
+    /// assert_eq!(map.pop_first(), Some((2, "b")));
+    /// assert_eq!(map.pop_first(), None);
+    /// ```
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        let Pair { key, value } = self.set.pop_first()?;
+        Some((key, value))
+    }
+
+    /// Removes and returns the last (i.e., largest-keyed) key-value pair in the map, if any.
+    ///
+    /// This is an `O(1)` operation, except when the last subarray is full,
+    /// in which case it is `O(√n)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut map: RotatedArrayMap<_, _> = vec![(1, "a"), (2, "b")].into_iter().collect();
+    /// assert_eq!(map.pop_last(), Some((2, "b")));
+    /// assert_eq!(map.pop_last(), Some((1, "a")));
+    /// assert_eq!(map.pop_last(), None);
+    /// ```
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        let Pair { key, value } = self.set.pop_last()?;
+        Some((key, value))
+    }
+
+    /// Inserts a key-value pair into the map.
+    ///
+    /// If the map did not have this key present, `None` is returned.
+    ///
+    /// If the map did have this key present, the value is updated, and the old
+    /// value is returned. The key is not updated, though.
+    ///
+    /// This is an `O(√n)` operation (or `O(lg n)` if the key is already present).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut map = RotatedArrayMap::new();
+    /// assert_eq!(map.insert(37, "a"), None);
+    /// assert_eq!(map.is_empty(), false);
+    ///
+    /// map.insert(37, "b");
+    /// assert_eq!(map.insert(37, "c"), Some("b"));
+    /// assert_eq!(map.get(&37), Some(&"c"));
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.find_raw_index(&key) {
+            Ok(raw_idx) => Some(mem::replace(&mut self.set.data[raw_idx].value, value)),
+            Err(raw_idx) => {
+                self.set.insert_raw(raw_idx, Pair { key, value });
+                None
+            }
+        }
+    }
+
+    /// Removes a key from the map, returning the value at the key if the key
+    /// was previously in the map.
+    ///
+    /// The key may be any borrowed form of the map's key type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the key type.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut map = RotatedArrayMap::new();
+    /// map.insert(1, "a");
+    /// assert_eq!(map.remove(&1), Some("a"));
+    /// assert_eq!(map.remove(&1), None);
+    /// ```
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    /// Removes a key from the map, returning the stored key and value if the key
+    /// was previously in the map.
+    ///
+    /// The key may be any borrowed form of the map's key type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the key type.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut map = RotatedArrayMap::new();
+    /// map.insert(1, "a");
+    /// assert_eq!(map.remove_entry(&1), Some((1, "a")));
+    /// assert_eq!(map.remove_entry(&1), None);
+    /// ```
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let raw_idx = self.find_raw_index(key).ok()?;
+        let Pair { key, value } = self.set.remove_raw(raw_idx);
+        Some((key, value))
+    }
+
+    /// Removes and returns the key-value pair, if any, with the given rank.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut map: RotatedArrayMap<_, _> = vec![(1, "a"), (2, "b"), (3, "c")].into_iter().collect();
+    /// assert_eq!(map.remove_at(1), Some((2, "b")));
+    /// assert_eq!(map.remove_at(2), None);
+    /// ```
+    pub fn remove_at(&mut self, rank: usize) -> Option<(K, V)> {
+        let Pair { key, value } = self.set.remove_at(rank)?;
+        Some((key, value))
+    }
+
+    /// Retains only the entries specified by the predicate.
+    ///
+    /// In other words, remove all pairs `(k, v)` such that `f(&k, &mut v)` returns `false`.
+    /// The entries are visited in ascending key order.
+    ///
+    /// This is an `O(n)` operation, regardless of how many entries are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut map: RotatedArrayMap<i32, i32> = (0..8).map(|x| (x, x * 10)).collect();
+    /// // Keep only the elements with even-numbered keys.
+    /// map.retain(|&k, _| k % 2 == 0);
+    /// assert!(map.into_iter().eq(vec![(0, 0), (2, 20), (4, 40), (6, 60)]));
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let subarray_count = self.set.min_indexes.len();
+        self.set.sort_subarrays(0, subarray_count);
+        // `Vec::retain_mut` keeps the unvisited entries if `f` panics, so the data stays sorted
+        RetainGuard::new(&mut self.set.data, &mut self.set.min_indexes)
+            .data
+            .retain_mut(|pair| f(&pair.key, &mut pair.value));
+        debug_assert!(self.set.layout().assert_invariants());
+    }
+
+    /// Gets a double-ended iterator over the entries of the map, sorted by key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut map = RotatedArrayMap::new();
+    /// map.insert(3, "c");
+    /// map.insert(2, "b");
+    /// map.insert(1, "a");
+    ///
+    /// let (first_key, first_value) = map.iter().next().unwrap();
+    /// assert_eq!((*first_key, *first_value), (1, "a"));
+    /// ```
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            iter: self.set.iter(),
+        }
+    }
+
+    /// Gets a mutable double-ended iterator over the entries of the map, sorted by key.
+    ///
+    /// This rotates every subarray of the map into sorted order, so it takes `O(n)` time
+    /// before the first entry is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut map = RotatedArrayMap::new();
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    /// map.insert("c", 3);
+    ///
+    /// // add 10 to the value if the key isn't "a"
+    /// for (key, value) in map.iter_mut() {
+    ///     if key != &"a" {
+    ///         *value += 10;
+    ///     }
+    /// }
+    /// assert_eq!(map.values().cloned().collect::<Vec<_>>(), [1, 12, 13]);
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        self.set.sort_subarrays(0, self.set.min_indexes.len());
+        IterMut {
+            iter: self.set.data.iter_mut(),
+        }
+    }
+
+    /// Gets a double-ended iterator over the keys of the map, in sorted order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut a = RotatedArrayMap::new();
+    /// a.insert(2, "b");
+    /// a.insert(1, "a");
+    ///
+    /// let keys: Vec<_> = a.keys().cloned().collect();
+    /// assert_eq!(keys, [1, 2]);
+    /// ```
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    /// Gets a double-ended iterator over the values of the map, in order by key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut a = RotatedArrayMap::new();
+    /// a.insert(1, "hello");
+    /// a.insert(2, "goodbye");
+    ///
+    /// let values: Vec<&str> = a.values().cloned().collect();
+    /// assert_eq!(values, ["hello", "goodbye"]);
+    /// ```
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+
+    /// Gets a mutable double-ended iterator over the values of the map, in order by key.
+    ///
+    /// This rotates every subarray of the map into sorted order, so it takes `O(n)` time
+    /// before the first value is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut a = RotatedArrayMap::new();
+    /// a.insert(1, String::from("hello"));
+    /// a.insert(2, String::from("goodbye"));
+    ///
+    /// for value in a.values_mut() {
+    ///     value.push_str("!");
+    /// }
+    ///
+    /// let values: Vec<String> = a.values().cloned().collect();
+    /// assert_eq!(values, [String::from("hello!"), String::from("goodbye!")]);
+    /// ```
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut {
+            inner: self.iter_mut(),
+        }
+    }
+
+    /// Constructs a double-ended iterator over a sub-range of entries in the map.
+    /// The simplest way is to use the range syntax `min..max`, thus `range(min..max)` will
+    /// yield entries from `min` (inclusive) to `max` (exclusive).
+    /// The range may also be entered as `(Bound<T>, Bound<T>)`, so for example
+    /// `range((Excluded(4), Included(10)))` will yield a left-exclusive, right-inclusive
+    /// range from 4 to 10.
+    ///
+    /// The bounds may be any borrowed form of the map's key type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the key type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    /// use std::ops::Bound::Included;
+    ///
+    /// let mut map = RotatedArrayMap::new();
+    /// map.insert(3, "a");
+    /// map.insert(5, "b");
+    /// map.insert(8, "c");
+    /// for (&key, &value) in map.range((Included(&4), Included(&8))) {
+    ///     println!("{}: {}", key, value);
+    /// }
+    /// assert_eq!(Some((&5, &"b")), map.range(4..).next());
+    /// ```
+    pub fn range<T, R>(&self, range: R) -> Iter<'_, K, V>
+    where
+        K: Borrow<T>,
+        T: Ord + ?Sized,
+        R: RangeBounds<T>,
+    {
+        Iter {
+            iter: crate::Iter::new(
+                self.set
+                    .layout()
+                    .get_range_by_key(range, |pair| pair.key.borrow()),
+            ),
+        }
+    }
+
+    /// Constructs a mutable double-ended iterator over a sub-range of entries in the map.
+    /// The simplest way is to use the range syntax `min..max`, thus `range(min..max)` will
+    /// yield entries from `min` (inclusive) to `max` (exclusive).
+    /// The range may also be entered as `(Bound<T>, Bound<T>)`, so for example
+    /// `range((Excluded(4), Included(10)))` will yield a left-exclusive, right-inclusive
+    /// range from 4 to 10.
+    ///
+    /// This rotates the subarrays overlapping the range into sorted order,
+    /// so it takes `O(m + √n)` time (for a range of `m` entries) before the first entry is returned.
+    ///
+    /// The bounds may be any borrowed form of the map's key type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the key type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMap;
+    ///
+    /// let mut map: RotatedArrayMap<&str, i32> =
+    ///     ["Alice", "Bob", "Carol", "Cheryl"].iter().map(|&s| (s, 0)).collect();
+    /// for (_, balance) in map.range_mut("B".."Cheryl") {
+    ///     *balance += 100;
+    /// }
+    /// for (name, balance) in &map {
+    ///     println!("{} => {}", name, balance);
+    /// }
+    /// assert_eq!(map.values().cloned().collect::<Vec<_>>(), [0, 100, 100, 0]);
+    /// ```
+    pub fn range_mut<T, R>(&mut self, range: R) -> IterMut<'_, K, V>
+    where
+        K: Borrow<T>,
+        T: Ord + ?Sized,
+        R: RangeBounds<T>,
+    {
+        let range = self
+            .set
+            .layout()
+            .get_range_by_key(range, |pair| pair.key.borrow());
+        let (start, end) = (range.start_index_inclusive, range.end_index_exclusive);
+        if start < end {
+            // once the overlapping subarrays are sorted, logical and raw indexes coincide
+            let first_subarray_idx = get_subarray_idx_from_array_idx(start);
+            let last_subarray_idx = get_subarray_idx_from_array_idx(end - 1);
+            self.set
+                .sort_subarrays(first_subarray_idx, last_subarray_idx + 1);
+        }
+        IterMut {
+            iter: self.set.data[start..end].iter_mut(),
+        }
+    }
+
+    fn find_raw_index<Q>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.set
+            .layout()
+            .find_raw_index_by(|pair| pair.key.borrow().cmp(key))
+    }
+}
+
+impl<K, V> PartialEq for RotatedArrayMap<K, V>
+where
+    K: Ord,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<K, V> Eq for RotatedArrayMap<K, V>
+where
+    K: Ord,
+    V: Eq,
+{
+}
+
+impl<K, V> Default for RotatedArrayMap<K, V>
+where
+    K: Ord,
+{
+    fn default() -> RotatedArrayMap<K, V> {
+        RotatedArrayMap::new()
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for RotatedArrayMap<K, V>
+where
+    K: Ord,
+{
+    /// Builds a map from an iterator of key-value pairs. If a key occurs more than once,
+    /// the map keeps its first occurrence and the value from its last occurrence,
+    /// just as if the pairs had been inserted in order.
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut pairs: Vec<_> = iter
+            .into_iter()
+            .map(|(key, value)| Pair { key, value })
+            .collect();
+        // a stable sort preserves the insertion order of duplicate keys
+        pairs.sort();
+        pairs.dedup_by(|next, prev| {
+            if next.key == prev.key {
+                mem::swap(&mut next.value, &mut prev.value);
+                true
+            } else {
+                false
+            }
+        });
+        RotatedArrayMap {
+            set: RotatedArraySet::from_sorted_dedup_unchecked(pairs),
+        }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a RotatedArrayMap<K, V>
+where
+    K: Ord,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a mut RotatedArrayMap<K, V>
+where
+    K: Ord,
+{
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<K, V> IntoIterator for RotatedArrayMap<K, V>
+where
+    K: Ord,
+{
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            iter: self.set.into_iter(),
+        }
+    }
+}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Iter { iter: self.iter }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V>
+where
+    K: Ord,
+{
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|pair| (&pair.key, &pair.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V>
+where
+    K: Ord,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|pair| (&pair.key, &pair.value))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> where K: Ord {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> where K: Ord {}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|pair| (&pair.key, &mut pair.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IterMut<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next_back()
+            .map(|pair| (&pair.key, &mut pair.value))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+
+impl<K, V> FusedIterator for IterMut<'_, K, V> {}
+
+impl<K, V> Iterator for IntoIter<K, V>
+where
+    K: Ord,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|pair| (pair.key, pair.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IntoIter<K, V>
+where
+    K: Ord,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|pair| (pair.key, pair.value))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> where K: Ord {}
+
+impl<K, V> FusedIterator for IntoIter<K, V> where K: Ord {}
+
+impl<K, V> Clone for Keys<'_, K, V> {
+    fn clone(&self) -> Self {
+        Keys {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V>
+where
+    K: Ord,
+{
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Keys<'_, K, V>
+where
+    K: Ord,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(key, _)| key)
+    }
+}
+
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> where K: Ord {}
+
+impl<K, V> FusedIterator for Keys<'_, K, V> where K: Ord {}
+
+impl<K, V> Clone for Values<'_, K, V> {
+    fn clone(&self) -> Self {
+        Values {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V>
+where
+    K: Ord,
+{
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Values<'_, K, V>
+where
+    K: Ord,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<K, V> ExactSizeIterator for Values<'_, K, V> where K: Ord {}
+
+impl<K, V> FusedIterator for Values<'_, K, V> where K: Ord {}
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for ValuesMut<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<K, V> ExactSizeIterator for ValuesMut<'_, K, V> {}
+
+impl<K, V> FusedIterator for ValuesMut<'_, K, V> {}
@@ -0,0 +1,1055 @@
+//! An ordered multiset based on a 2-level rotated array.
+//!
+//! The multiset shares the layout and search machinery of [`RotatedArraySet`], which
+//! only depends on elements being in nondecreasing order. Equal elements are kept in
+//! insertion order, so they can be told apart by any state not covered by `Ord`.
+//!
+//! [`RotatedArraySet`]: ../struct.RotatedArraySet.html
+
+use core::borrow::Borrow;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::ops::RangeBounds;
+
+use alloc::vec::Vec;
+
+use crate::{percentile_rank, Chunks, Drain, ExtractIf, IntoIter, Iter, Range, RotatedArraySet};
+
+/// An ordered multiset based on a 2-level rotated array.
+///
+/// Unlike [`RotatedArraySet`], inserting a value that is already present
+/// always succeeds, and adds another occurrence of the value.
+///
+/// # Examples
+///
+/// ```
+/// use rotated_array_set::RotatedArrayMultiset;
+///
+/// // Type inference lets us omit an explicit type signature (which
+/// // would be `RotatedArrayMultiset<u32>` in this example).
+/// let mut latencies = RotatedArrayMultiset::new();
+///
+/// // Record some request latencies (in milliseconds).
+/// for &latency in &[12, 7, 12, 30, 7, 12] {
+///     latencies.insert(latency);
+/// }
+///
+/// // Count the occurrences of a specific one.
+/// assert_eq!(latencies.count(&12), 3);
+///
+/// // Find the median.
+/// assert_eq!(latencies.select(latencies.len() / 2), Some(&12));
+///
+/// // Discard one outlier.
+/// latencies.remove_one(&30);
+///
+/// // Iterate over everything.
+/// for latency in &latencies {
+///     println!("{}", latency);
+/// }
+/// ```
+///
+/// [`RotatedArraySet`]: struct.RotatedArraySet.html
+#[derive(Debug, Clone)]
+pub struct RotatedArrayMultiset<T> {
+    // NB: the set's value-based methods assume uniqueness, so only its
+    // positional methods and bound searches may be used here.
+    array: RotatedArraySet<T>,
+}
+
+impl<T> RotatedArrayMultiset<T>
+where
+    T: Ord,
+{
+    /// Makes a new, empty `RotatedArrayMultiset` without any heap allocations.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let mut multiset: RotatedArrayMultiset<i32> = RotatedArrayMultiset::new();
+    /// ```
+    pub fn new() -> Self {
+        RotatedArrayMultiset {
+            array: RotatedArraySet::new(),
+        }
+    }
+
+    /// Constructs a new, empty `RotatedArrayMultiset<T>` with the specified capacity.
+    ///
+    /// The multiset will be able to hold exactly `capacity` elements without
+    /// reallocating. If `capacity` is 0, the multiset will not allocate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let mut multiset = RotatedArrayMultiset::with_capacity(10);
+    /// assert_eq!(multiset.len(), 0);
+    /// multiset.insert(1);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        RotatedArrayMultiset {
+            array: RotatedArraySet::with_capacity(capacity),
+        }
+    }
+
+    /// Clears the multiset, removing all values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let mut v = RotatedArrayMultiset::new();
+    /// v.insert(1);
+    /// v.insert(1);
+    /// v.clear();
+    /// assert!(v.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.array.clear();
+    }
+
+    /// Returns the number of elements in the multiset, counting duplicates.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let mut v = RotatedArrayMultiset::new();
+    /// assert_eq!(v.len(), 0);
+    /// v.insert(1);
+    /// v.insert(1);
+    /// assert_eq!(v.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    /// Returns `true` if the multiset contains no elements.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let mut v = RotatedArrayMultiset::new();
+    /// assert!(v.is_empty());
+    /// v.insert(1);
+    /// assert!(!v.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.array.is_empty()
+    }
+
+    /// Returns `true` if the multiset contains at least one occurrence of the value.
+    ///
+    /// The value may be any borrowed form of the multiset's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let multiset: RotatedArrayMultiset<_> = vec![1, 2, 2].into();
+    /// assert_eq!(multiset.contains(&2), true);
+    /// assert_eq!(multiset.contains(&4), false);
+    /// ```
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(value).is_some()
+    }
+
+    /// Returns a reference to the first occurrence of the value in the multiset, if any.
+    ///
+    /// The value may be any borrowed form of the multiset's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let multiset: RotatedArrayMultiset<_> = vec![1, 2, 2].into();
+    /// assert_eq!(multiset.get(&2), Some(&2));
+    /// assert_eq!(multiset.get(&4), None);
+    /// ```
+    pub fn get<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let raw_idx = self.find_first_raw_index(value).ok()?;
+        Some(&self.array.data[raw_idx])
+    }
+
+    /// Returns the number of occurrences of the value in the multiset.
+    ///
+    /// The value may be any borrowed form of the multiset's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let multiset: RotatedArrayMultiset<_> = vec![1, 2, 2, 3].into();
+    /// assert_eq!(multiset.count(&2), 2);
+    /// assert_eq!(multiset.count(&4), 0);
+    /// ```
+    pub fn count<Q>(&self, value: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (start, end) = self.equal_rank_bounds(value);
+        end - start
+    }
+
+    /// Returns the rank of the first occurrence of the value in the multiset if it exists
+    /// (as `Result::Ok`), or the rank of its largest predecessor plus one, if it does not
+    /// exist (as `Result::Err`).
+    ///
+    /// The value may be any borrowed form of the multiset's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let multiset: RotatedArrayMultiset<_> = vec![1, 2, 2, 3].into();
+    /// assert_eq!(multiset.rank_first(&2), Ok(1));
+    /// assert_eq!(multiset.rank_first(&4), Err(4));
+    /// ```
+    pub fn rank_first<Q>(&self, value: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (start, end) = self.equal_rank_bounds(value);
+        if start < end {
+            Ok(start)
+        } else {
+            Err(start)
+        }
+    }
+
+    /// Returns the rank of the last occurrence of the value in the multiset if it exists
+    /// (as `Result::Ok`), or the rank of its largest predecessor plus one, if it does not
+    /// exist (as `Result::Err`).
+    ///
+    /// The value may be any borrowed form of the multiset's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let multiset: RotatedArrayMultiset<_> = vec![1, 2, 2, 3].into();
+    /// assert_eq!(multiset.rank_last(&2), Ok(2));
+    /// assert_eq!(multiset.rank_last(&0), Err(0));
+    /// ```
+    pub fn rank_last<Q>(&self, value: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (start, end) = self.equal_rank_bounds(value);
+        if start < end {
+            Ok(end - 1)
+        } else {
+            Err(start)
+        }
+    }
+
+    /// Returns a reference to the element with the given rank, if any.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let multiset: RotatedArrayMultiset<_> = vec![1, 2, 2].into();
+    /// assert_eq!(multiset.select(2), Some(&2));
+    /// assert_eq!(multiset.select(3), None);
+    /// ```
+    pub fn select(&self, rank: usize) -> Option<&T> {
+        self.array.select(rank)
+    }
+
+    /// Returns a reference to the element at the given quantile of the multiset, if any,
+    /// i.e., the element with rank `floor(q * (len - 1))`.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `q` is not in the range `[0, 1]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let multiset: RotatedArrayMultiset<_> = vec![1, 1, 1, 2, 5].into();
+    /// assert_eq!(multiset.quantile(0.5), Some(&1));
+    /// assert_eq!(multiset.quantile(0.75), Some(&2));
+    /// ```
+    pub fn quantile(&self, q: f64) -> Option<&T> {
+        self.array.quantile(q)
+    }
+
+    /// Returns a reference to the median element of the multiset, if any. If the multiset
+    /// has an even number of elements, this is the lower of the two middle elements.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let multiset: RotatedArrayMultiset<_> = vec![1, 3, 3, 7].into();
+    /// assert_eq!(multiset.median(), Some(&3));
+    /// ```
+    pub fn median(&self) -> Option<&T> {
+        self.array.median()
+    }
+
+    /// Returns the percentile rank of the given value in the multiset, i.e., the percentage
+    /// of elements in the multiset that are less than the given value, counting elements
+    /// equal to it as half. Returns 0 if the multiset is empty.
+    ///
+    /// The value may be any borrowed form of the multiset's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let multiset: RotatedArrayMultiset<_> = vec![1, 2, 2, 3].into();
+    /// assert_eq!(multiset.percentile_rank(&2), 50.0);
+    /// assert_eq!(multiset.percentile_rank(&3), 87.5);
+    /// ```
+    pub fn percentile_rank<Q>(&self, value: &Q) -> f64
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (start, end) = self.equal_rank_bounds(value);
+        percentile_rank(start, end - start, self.len())
+    }
+
+    /// Returns the number of elements in the given range, including every occurrence
+    /// of each value in the range. The range may be specified as for [`range`].
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let multiset: RotatedArrayMultiset<_> = vec![3, 5, 5, 8].into();
+    /// assert_eq!(multiset.count_range(4..), 3);
+    /// assert_eq!(multiset.count_range(4..8), 2);
+    /// ```
+    ///
+    /// [`range`]: #method.range
+    pub fn count_range<K, R>(&self, range: R) -> usize
+    where
+        T: Borrow<K>,
+        K: Ord + ?Sized,
+        R: RangeBounds<K>,
+    {
+        self.array.count_range(range)
+    }
+
+    /// Returns a reference to the element with the given rank within the given range,
+    /// which may be specified as for [`range`], if any.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let multiset: RotatedArrayMultiset<_> = vec![3, 5, 5, 8].into();
+    /// assert_eq!(multiset.nth_in_range(4.., 1), Some(&5));
+    /// assert_eq!(multiset.nth_in_range(4..8, 2), None);
+    /// ```
+    ///
+    /// [`range`]: #method.range
+    pub fn nth_in_range<K, R>(&self, range: R, n: usize) -> Option<&T>
+    where
+        T: Borrow<K>,
+        K: Ord + ?Sized,
+        R: RangeBounds<K>,
+    {
+        self.array.nth_in_range(range, n)
+    }
+
+    /// Returns a reference to the first (i.e., smallest) element in the multiset, if any.
+    /// If there are several, this is the one inserted first.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let multiset: RotatedArrayMultiset<_> = vec![2, 1, 1].into();
+    /// assert_eq!(multiset.first(), Some(&1));
+    /// ```
+    pub fn first(&self) -> Option<&T> {
+        self.array.first()
+    }
+
+    /// Returns a reference to the last (i.e., largest) element in the multiset, if any.
+    /// If there are several, this is the one inserted last.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let multiset: RotatedArrayMultiset<_> = vec![2, 1, 2].into();
+    /// assert_eq!(multiset.last(), Some(&2));
+    /// ```
+    pub fn last(&self) -> Option<&T> {
+        self.array.last()
+    }
+
+    /// Removes and returns the first (i.e., smallest) element in the multiset, if any.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let mut multiset: RotatedArrayMultiset<_> = vec![1, 1].into();
+    /// assert_eq!(multiset.pop_first(), Some(1));
+    /// assert_eq!(multiset.pop_first(), Some(1));
+    /// assert_eq!(multiset.pop_first(), None);
+    /// ```
+    pub fn pop_first(&mut self) -> Option<T> {
+        self.array.pop_first()
+    }
+
+    /// Removes and returns the last (i.e., largest) element in the multiset, if any.
+    ///
+    /// This is an `O(1)` operation, except when the last subarray is full,
+    /// in which case it is `O(√n)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let mut multiset: RotatedArrayMultiset<_> = vec![1, 2].into();
+    /// assert_eq!(multiset.pop_last(), Some(2));
+    /// assert_eq!(multiset.pop_last(), Some(1));
+    /// assert_eq!(multiset.pop_last(), None);
+    /// ```
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.array.pop_last()
+    }
+
+    /// Adds a value to the multiset. The new value is placed after any
+    /// equal values already present.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let mut multiset = RotatedArrayMultiset::new();
+    ///
+    /// multiset.insert(2);
+    /// multiset.insert(2);
+    /// assert_eq!(multiset.len(), 2);
+    /// ```
+    pub fn insert(&mut self, value: T) {
+        let insert_idx = self.array.layout().upper_bound_by_key(&value, |x| x);
+        self.array.insert_raw(insert_idx, value);
+    }
+
+    /// Adds all values from an iterator that yields them in ascending order, merging
+    /// them into the multiset. The new values are placed after any equal values
+    /// already present.
+    ///
+    /// This takes `O(k√n)` time for a small number `k` of values, and `O(n + k)` time otherwise,
+    /// since the values don't need to be sorted before merging. (Unsorted batches can be added
+    /// with `extend` instead.) The values are merged in place, without any storage beyond
+    /// the capacity needed to hold them.
+    ///
+    /// It is a logic error for the iterator not to yield its values in ascending order.
+    /// The behavior resulting from such a logic error is not specified (but will not be
+    /// undefined behavior), and is only detected in debug builds, where it causes a panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let mut multiset: RotatedArrayMultiset<_> = vec![1, 5, 9].into();
+    /// multiset.insert_sorted_batch(vec![2, 5, 5, 8]);
+    /// assert!(multiset.iter().eq([1, 2, 5, 5, 5, 8, 9].iter()));
+    /// ```
+    pub fn insert_sorted_batch<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let batch: Vec<T> = iter.into_iter().collect();
+        self.insert_batch(batch);
+    }
+
+    // Inserts a sorted batch of values, either one at a time or by merging and rebuilding,
+    // depending on which is cheaper.
+    fn insert_batch(&mut self, mut batch: Vec<T>) {
+        if self.array.prefers_bulk_insert(batch.len()) {
+            self.array.merge_batch(batch, false);
+        } else {
+            for value in batch.drain(..) {
+                self.insert(value);
+            }
+        }
+    }
+
+    /// Removes the first occurrence of a value from the multiset, and returns it
+    /// if it was present.
+    ///
+    /// The value may be any borrowed form of the multiset's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let mut multiset: RotatedArrayMultiset<_> = vec![1, 2, 2].into();
+    /// assert_eq!(multiset.remove_one(&2), Some(2));
+    /// assert_eq!(multiset.remove_one(&2), Some(2));
+    /// assert_eq!(multiset.remove_one(&2), None);
+    /// ```
+    pub fn remove_one<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let raw_idx = self.find_first_raw_index(value).ok()?;
+        Some(self.array.remove_raw(raw_idx))
+    }
+
+    /// Removes and returns the element, if any, with the given rank.
+    ///
+    /// This is an `O(√n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let mut multiset: RotatedArrayMultiset<_> = vec![1, 2, 2].into();
+    /// assert_eq!(multiset.remove_at(1), Some(2));
+    /// assert_eq!(multiset.remove_at(2), None);
+    /// assert_eq!(multiset.count(&2), 1);
+    /// ```
+    pub fn remove_at(&mut self, rank: usize) -> Option<T> {
+        self.array.remove_at(rank)
+    }
+
+    /// Removes all occurrences of a value from the multiset, and returns
+    /// the number of elements removed.
+    ///
+    /// The value may be any borrowed form of the multiset's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(min(k√n, n))` operation, where `k` is the number of occurrences.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let mut multiset: RotatedArrayMultiset<_> = vec![1, 2, 2, 3].into();
+    /// assert_eq!(multiset.remove_all(&2), 2);
+    /// assert_eq!(multiset.remove_all(&2), 0);
+    /// assert_eq!(multiset.len(), 2);
+    /// ```
+    pub fn remove_all<Q>(&mut self, value: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (start, end) = self.equal_rank_bounds(value);
+        let count = end - start;
+        if count.saturating_mul(self.array.min_indexes.len()) <= self.len() {
+            // each removal costs `O(√n)`, and the first remaining occurrence keeps the same rank
+            for _ in 0..count {
+                let raw_idx = self.array.layout().get_raw_idx_from_logical_idx(start);
+                self.array.remove_raw(raw_idx);
+            }
+        } else {
+            // once the array is sorted, removing a contiguous run costs `O(n)`
+            let subarray_count = self.array.min_indexes.len();
+            self.array.sort_subarrays(0, subarray_count);
+            self.array.data.drain(start..end);
+            self.array.init_min_indexes();
+        }
+        count
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all elements `e` such that `f(&e)` returns `false`.
+    /// The elements are visited in ascending order.
+    ///
+    /// This is an `O(n)` operation, regardless of how many elements are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let mut multiset: RotatedArrayMultiset<_> = vec![1, 2, 2, 3].into();
+    /// multiset.retain(|&k| k != 2);
+    /// assert!(multiset.iter().eq([1, 3].iter()));
+    /// ```
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.array.retain(f);
+    }
+
+    /// Creates an iterator that visits all elements in ascending order and uses a closure
+    /// to determine if an element should be removed.
+    ///
+    /// See [`RotatedArraySet::extract_if`] for details.
+    ///
+    /// [`RotatedArraySet::extract_if`]: struct.RotatedArraySet.html#method.extract_if
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let mut multiset: RotatedArrayMultiset<i32> = vec![1, 2, 2, 3].into();
+    /// let evens: Vec<_> = multiset.extract_if(|v| v % 2 == 0).collect();
+    /// assert_eq!(evens, [2, 2]);
+    /// assert_eq!(multiset.len(), 2);
+    /// ```
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, T, F>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.array.extract_if(pred)
+    }
+
+    /// Gets a double-ended iterator over all occurrences of a value in the multiset.
+    ///
+    /// The value may be any borrowed form of the multiset's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let multiset: RotatedArrayMultiset<_> = vec![1, 2, 2, 3].into();
+    /// let twos: Vec<_> = multiset.equal_range(&2).collect();
+    /// assert_eq!(twos, [&2, &2]);
+    /// assert_eq!(multiset.equal_range(&4).next(), None);
+    /// ```
+    pub fn equal_range<Q>(&self, value: &Q) -> Iter<'_, T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (start, end) = self.equal_rank_bounds(value);
+        Iter::new(Range::with_bounds(self.array.layout(), start, end))
+    }
+
+    /// Gets a double-ended iterator that visits the values in the multiset in ascending order,
+    /// with equal values in insertion order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let multiset: RotatedArrayMultiset<usize> = vec![3, 1, 3].into();
+    /// let mut multiset_iter = multiset.iter();
+    /// assert_eq!(multiset_iter.next(), Some(&1));
+    /// assert_eq!(multiset_iter.next(), Some(&3));
+    /// assert_eq!(multiset_iter.next(), Some(&3));
+    /// assert_eq!(multiset_iter.next(), None);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.array.iter()
+    }
+
+    /// Gets a double-ended iterator over the elements in the multiset as sorted slices,
+    /// in ascending order. Concatenating the slices gives the same sequence as [`iter`].
+    ///
+    /// [`iter`]: struct.RotatedArrayMultiset.html#method.iter
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let multiset: RotatedArrayMultiset<u32> = vec![3, 1, 3].into();
+    /// assert_eq!(multiset.chunks().map(|chunk| chunk.len()).sum::<usize>(), 3);
+    /// ```
+    pub fn chunks(&self) -> Chunks<'_, T> {
+        self.array.chunks()
+    }
+
+    /// Constructs a double-ended iterator over a sub-range of elements in the multiset.
+    /// The simplest way is to use the range syntax `min..max`, thus `range(min..max)` will
+    /// yield elements from `min` (inclusive) to `max` (exclusive), including every occurrence
+    /// of each value in the range.
+    /// The range may also be entered as `(Bound<T>, Bound<T>)`, so for example
+    /// `range((Excluded(4), Included(10)))` will yield a left-exclusive, right-inclusive
+    /// range from 4 to 10.
+    ///
+    /// The bounds may be any borrowed form of the multiset's value type,
+    /// but the ordering on the borrowed form *must* match the
+    /// ordering on the value type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    /// use std::ops::Bound::Included;
+    ///
+    /// let multiset: RotatedArrayMultiset<_> = vec![3, 5, 5, 8].into();
+    /// for &elem in multiset.range((Included(&4), Included(&8))) {
+    ///     println!("{}", elem);
+    /// }
+    /// assert_eq!(multiset.range(4..).count(), 3);
+    /// ```
+    pub fn range<K, R>(&self, range: R) -> Iter<'_, T>
+    where
+        T: Borrow<K>,
+        K: Ord + ?Sized,
+        R: RangeBounds<K>,
+    {
+        self.array.range(range)
+    }
+
+    /// Removes the specified range of elements from the multiset in bulk, including
+    /// every occurrence of each value in the range, and returns all removed elements
+    /// as an iterator (in ascending order).
+    /// The range may be specified as for [`range`].
+    ///
+    /// The elements are removed even if the iterator is not consumed.
+    ///
+    /// This is an `O(n)` operation, regardless of how many elements are removed.
+    ///
+    /// [`range`]: struct.RotatedArrayMultiset.html#method.range
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let mut events: RotatedArrayMultiset<_> = vec![1, 2, 2, 3, 5].into();
+    /// let expired: Vec<_> = events.drain(..3).collect();
+    /// assert_eq!(expired, [1, 2, 2]);
+    /// assert_eq!(events.len(), 2);
+    /// ```
+    pub fn drain<K, R>(&mut self, range: R) -> Drain<'_, T>
+    where
+        T: Borrow<K>,
+        K: Ord + ?Sized,
+        R: RangeBounds<K>,
+    {
+        self.array.drain(range)
+    }
+
+    /// Removes the elements with the specified range of ranks from the multiset in bulk,
+    /// returning all removed elements as an iterator (in ascending order).
+    ///
+    /// The elements are removed even if the iterator is not consumed.
+    ///
+    /// This is an `O(n)` operation, regardless of how many elements are removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the starting rank is greater than the end rank,
+    /// or if the end rank is greater than the length of the multiset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let mut multiset: RotatedArrayMultiset<_> = vec![1, 1, 2, 3].into();
+    /// let smallest: Vec<_> = multiset.drain_ranks(..3).collect();
+    /// assert_eq!(smallest, [1, 1, 2]);
+    /// ```
+    pub fn drain_ranks<R>(&mut self, range: R) -> Drain<'_, T>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.array.drain_ranks(range)
+    }
+
+    /// Removes the specified range of elements from the multiset in bulk, including
+    /// every occurrence of each value in the range, and returns the number of elements removed.
+    /// The range may be specified as for [`range`].
+    ///
+    /// This is an `O(n)` operation, regardless of how many elements are removed.
+    ///
+    /// [`range`]: struct.RotatedArrayMultiset.html#method.range
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::RotatedArrayMultiset;
+    ///
+    /// let mut multiset: RotatedArrayMultiset<_> = vec![1, 2, 2, 3].into();
+    /// assert_eq!(multiset.remove_range(2..), 3);
+    /// assert_eq!(multiset.len(), 1);
+    /// ```
+    pub fn remove_range<K, R>(&mut self, range: R) -> usize
+    where
+        T: Borrow<K>,
+        K: Ord + ?Sized,
+        R: RangeBounds<K>,
+    {
+        self.array.remove_range(range)
+    }
+
+    // Constructs a multiset from a vector whose elements are already sorted, without sorting it.
+    pub(crate) fn from_sorted_unchecked(vec: Vec<T>) -> Self {
+        let mut array = RotatedArraySet {
+            data: vec,
+            min_indexes: Vec::new(),
+        };
+        array.init_min_indexes();
+        RotatedArrayMultiset { array }
+    }
+
+    // Returns the ranks of the first occurrence of `value` and one past its last occurrence.
+    fn equal_rank_bounds<Q>(&self, value: &Q) -> (usize, usize)
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let layout = self.array.layout();
+        let start = layout.lower_bound_by_key(value, |x| x.borrow());
+        let end = layout.upper_bound_by_key(value, |x| x.borrow());
+        (
+            layout.get_logical_idx_from_raw_idx(start),
+            layout.get_logical_idx_from_raw_idx(end),
+        )
+    }
+
+    // Returns either the raw index of the first occurrence of `value`, or its raw insertion point.
+    fn find_first_raw_index<Q>(&self, value: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let raw_idx = self
+            .array
+            .layout()
+            .lower_bound_by_key(value, |x| x.borrow());
+        match self.array.data.get(raw_idx) {
+            Some(elem) if elem.borrow() == value => Ok(raw_idx),
+            _ => Err(raw_idx),
+        }
+    }
+}
+
+impl<T> PartialEq for RotatedArrayMultiset<T>
+where
+    T: Ord,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T> Eq for RotatedArrayMultiset<T> where T: Ord {}
+
+impl<T> Hash for RotatedArrayMultiset<T>
+where
+    T: Ord + Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.array.hash(state);
+    }
+}
+
+impl<T> Extend<T> for RotatedArrayMultiset<T>
+where
+    T: Ord,
+{
+    /// Adds all values from an iterator, just as if they had been inserted in order.
+    ///
+    /// Small batches are inserted one value at a time, and large batches are sorted and
+    /// merged into the multiset in a single pass.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut batch: Vec<T> = iter.into_iter().collect();
+        // a stable sort preserves the insertion order of equal values
+        batch.sort();
+        self.insert_batch(batch);
+    }
+}
+
+impl<'a, T> Extend<&'a T> for RotatedArrayMultiset<T>
+where
+    T: 'a + Ord + Copy,
+{
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().cloned());
+    }
+}
+
+impl<T> Default for RotatedArrayMultiset<T>
+where
+    T: Ord,
+{
+    fn default() -> RotatedArrayMultiset<T> {
+        RotatedArrayMultiset::new()
+    }
+}
+
+impl<T> From<Vec<T>> for RotatedArrayMultiset<T>
+where
+    T: Ord,
+{
+    fn from(mut vec: Vec<T>) -> Self {
+        // a stable sort preserves the insertion order of equal values
+        vec.sort();
+        Self::from_sorted_unchecked(vec)
+    }
+}
+
+impl<T> From<RotatedArrayMultiset<T>> for Vec<T>
+where
+    T: Ord,
+{
+    fn from(multiset: RotatedArrayMultiset<T>) -> Vec<T> {
+        multiset.array.into()
+    }
+}
+
+impl<T> FromIterator<T> for RotatedArrayMultiset<T>
+where
+    T: Ord,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Vec::from_iter(iter).into()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RotatedArrayMultiset<T>
+where
+    T: Ord,
+{
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> IntoIterator for RotatedArrayMultiset<T>
+where
+    T: Ord,
+{
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.array.into_iter()
+    }
+}
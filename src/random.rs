@@ -0,0 +1,299 @@
+//! Random sampling from a `RotatedArraySet`, available with the `rand` feature.
+//!
+//! Since `select` takes constant time, a uniformly random value can be chosen by
+//! choosing a uniformly random rank, without iterating. Weighted values are chosen
+//! by choosing a rank from the cumulative weights of the values in rank order.
+
+use core::borrow::Borrow;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::ops::{AddAssign, RangeBounds};
+
+use rand::distributions::uniform::{SampleBorrow, SampleUniform};
+use rand::distributions::{Distribution, WeightedError, WeightedIndex};
+use rand::seq::index::{self, IndexVecIntoIter};
+use rand::Rng;
+
+use crate::RotatedArraySet;
+
+/// An iterator over values sampled without replacement from a `RotatedArraySet`.
+///
+/// This `struct` is created by the [`choose_multiple`] and [`shuffle_iter`] methods on
+/// [`RotatedArraySet`]. See their documentation for more.
+///
+/// [`RotatedArraySet`]: struct.RotatedArraySet.html
+/// [`choose_multiple`]: struct.RotatedArraySet.html#method.choose_multiple
+/// [`shuffle_iter`]: struct.RotatedArraySet.html#method.shuffle_iter
+#[derive(Debug, Clone)]
+pub struct Sample<'a, T: 'a> {
+    set: &'a RotatedArraySet<T>,
+    ranks: IndexVecIntoIter,
+}
+
+/// A distribution over the values of a `RotatedArraySet`, each chosen with probability
+/// proportional to its weight.
+///
+/// This `struct` is created by the [`weighted_sampler`] method on [`RotatedArraySet`].
+/// See its documentation for more.
+///
+/// [`RotatedArraySet`]: struct.RotatedArraySet.html
+/// [`weighted_sampler`]: struct.RotatedArraySet.html#method.weighted_sampler
+pub struct WeightedSampler<'a, T: 'a, X>
+where
+    X: SampleUniform + PartialOrd,
+{
+    set: &'a RotatedArraySet<T>,
+    // the cumulative weights of the values, indexed by rank
+    ranks: WeightedIndex<X>,
+}
+
+impl<T, X> Clone for WeightedSampler<'_, T, X>
+where
+    X: SampleUniform + PartialOrd,
+    WeightedIndex<X>: Clone,
+{
+    fn clone(&self) -> Self {
+        WeightedSampler {
+            set: self.set,
+            ranks: self.ranks.clone(),
+        }
+    }
+}
+
+impl<T, X> fmt::Debug for WeightedSampler<'_, T, X>
+where
+    T: fmt::Debug,
+    X: SampleUniform + PartialOrd,
+    WeightedIndex<X>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WeightedSampler")
+            .field("set", &self.set)
+            .field("ranks", &self.ranks)
+            .finish()
+    }
+}
+
+impl<T> RotatedArraySet<T>
+where
+    T: Ord,
+{
+    /// Returns a reference to a uniformly random value in the set, or `None` if
+    /// the set is empty.
+    ///
+    /// This is a constant-time operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rand::thread_rng;
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set: RotatedArraySet<_> = vec![1, 2, 3].into();
+    /// let value = set.choose(&mut thread_rng()).unwrap();
+    /// assert!(set.contains(value));
+    /// ```
+    pub fn choose<R>(&self, rng: &mut R) -> Option<&T>
+    where
+        R: Rng + ?Sized,
+    {
+        if self.is_empty() {
+            None
+        } else {
+            self.select(rng.gen_range(0, self.len()))
+        }
+    }
+
+    /// Returns an iterator over `amount` distinct values chosen uniformly at random
+    /// from the set, in random order, or over all values of the set (also in random order)
+    /// if it has fewer than `amount` values.
+    ///
+    /// Ranks are sampled without replacement in `O(amount)` time and space, and each
+    /// value is then found in constant time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rand::thread_rng;
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set: RotatedArraySet<_> = (0..100).collect();
+    /// let mut sample: Vec<_> = set.choose_multiple(&mut thread_rng(), 10).collect();
+    /// sample.sort();
+    /// sample.dedup();
+    /// assert_eq!(sample.len(), 10);
+    /// ```
+    pub fn choose_multiple<R>(&self, rng: &mut R, amount: usize) -> Sample<'_, T>
+    where
+        R: Rng + ?Sized,
+    {
+        let amount = amount.min(self.len());
+        Sample {
+            set: self,
+            ranks: index::sample(rng, self.len(), amount).into_iter(),
+        }
+    }
+
+    /// Returns a reference to a uniformly random value in the given range, or `None` if
+    /// the range is empty. The range may be specified as for [`range`].
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rand::thread_rng;
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set: RotatedArraySet<_> = (0..100).collect();
+    /// let value = set.choose_in_range(10..20, &mut thread_rng()).unwrap();
+    /// assert!((10..20).contains(value));
+    /// assert_eq!(set.choose_in_range(200.., &mut thread_rng()), None);
+    /// ```
+    ///
+    /// [`range`]: #method.range
+    pub fn choose_in_range<K, Rb, R>(&self, range: Rb, rng: &mut R) -> Option<&T>
+    where
+        T: Borrow<K>,
+        K: Ord + ?Sized,
+        Rb: RangeBounds<K>,
+        R: Rng + ?Sized,
+    {
+        let range = self.layout().get_range(range);
+        if range.start_index_inclusive == range.end_index_exclusive {
+            None
+        } else {
+            let rank = rng.gen_range(range.start_index_inclusive, range.end_index_exclusive);
+            self.select(rank)
+        }
+    }
+
+    /// Returns a reference to a random value in the set, chosen with probability
+    /// proportional to its weight as given by `weight`.
+    ///
+    /// Returns an error if the set is empty, if any weight is negative, or if all weights
+    /// are zero.
+    ///
+    /// This takes `O(n)` time and space to compute the cumulative weights. Use
+    /// [`weighted_sampler`] instead to choose several values with the same weights.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rand::thread_rng;
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set: RotatedArraySet<u32> = (0..10).collect();
+    /// // only odd values have nonzero weight
+    /// let value = set.choose_weighted(&mut thread_rng(), |v| v % 2).unwrap();
+    /// assert_eq!(value % 2, 1);
+    /// ```
+    ///
+    /// [`weighted_sampler`]: #method.weighted_sampler
+    pub fn choose_weighted<R, F, B, X>(&self, rng: &mut R, weight: F) -> Result<&T, WeightedError>
+    where
+        R: Rng + ?Sized,
+        F: Fn(&T) -> B,
+        B: SampleBorrow<X>,
+        X: SampleUniform + for<'b> AddAssign<&'b X> + PartialOrd<X> + Clone + Default,
+    {
+        Ok(self.weighted_sampler(weight)?.sample(rng))
+    }
+
+    /// Returns a distribution over the values of the set, from which each value is sampled
+    /// with probability proportional to its weight as given by `weight`.
+    ///
+    /// Returns an error if the set is empty, if any weight is negative, or if all weights
+    /// are zero.
+    ///
+    /// Building the distribution takes `O(n)` time and space, and each sample then takes
+    /// `O(lg n)` time to search the cumulative weights for a rank.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rand::distributions::Distribution;
+    /// use rand::thread_rng;
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set: RotatedArraySet<u32> = vec![1, 2, 4].into();
+    /// // 4 is sampled four times as often as 1
+    /// let sampler = set.weighted_sampler(|&v| v).unwrap();
+    /// let mut rng = thread_rng();
+    /// for value in sampler.sample_iter(&mut rng).take(100) {
+    ///     assert!(set.contains(value));
+    /// }
+    /// ```
+    pub fn weighted_sampler<F, B, X>(
+        &self,
+        weight: F,
+    ) -> Result<WeightedSampler<'_, T, X>, WeightedError>
+    where
+        F: Fn(&T) -> B,
+        B: SampleBorrow<X>,
+        X: SampleUniform + for<'b> AddAssign<&'b X> + PartialOrd<X> + Clone + Default,
+    {
+        Ok(WeightedSampler {
+            set: self,
+            ranks: WeightedIndex::new(self.iter().map(weight))?,
+        })
+    }
+
+    /// Returns an iterator over all values of the set in a uniformly random order.
+    ///
+    /// This takes `O(n)` time and space to shuffle the ranks up front.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rand::thread_rng;
+    /// use rotated_array_set::RotatedArraySet;
+    ///
+    /// let set: RotatedArraySet<_> = (0..10).collect();
+    /// let mut shuffled: Vec<_> = set.shuffle_iter(&mut thread_rng()).cloned().collect();
+    /// shuffled.sort();
+    /// assert_eq!(shuffled, (0..10).collect::<Vec<_>>());
+    /// ```
+    pub fn shuffle_iter<R>(&self, rng: &mut R) -> Sample<'_, T>
+    where
+        R: Rng + ?Sized,
+    {
+        self.choose_multiple(rng, self.len())
+    }
+}
+
+impl<'a, T> Iterator for Sample<'a, T>
+where
+    T: Ord,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let rank = self.ranks.next()?;
+        self.set.select(rank)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ranks.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for Sample<'_, T> where T: Ord {}
+
+impl<T> FusedIterator for Sample<'_, T> where T: Ord {}
+
+impl<'a, T, X> Distribution<&'a T> for WeightedSampler<'a, T, X>
+where
+    T: Ord,
+    X: SampleUniform + PartialOrd,
+{
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> &'a T {
+        let rank = self.ranks.sample(rng);
+        self.set.select(rank).unwrap()
+    }
+}
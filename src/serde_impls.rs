@@ -0,0 +1,187 @@
+//! `Serialize` and `Deserialize` implementations, available with the `serde` feature.
+//!
+//! Sets and multisets are serialized as sequences of their values in ascending order, and
+//! maps as maps of their entries in ascending key order. When deserializing, input that is
+//! already in ascending order (as produced by serialization) is laid out directly in linear
+//! time, without sorting. Other input is sorted first, and duplicates are then handled exactly
+//! as when collecting an iterator: a set keeps the first of several equal values, a multiset
+//! keeps all of them, and a map keeps the value from the last of several equal keys.
+
+use core::cmp::min;
+use core::fmt;
+use core::marker::PhantomData;
+
+use alloc::vec::Vec;
+
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+use crate::{RotatedArrayMap, RotatedArrayMultiset, RotatedArraySet};
+
+// Don't trust a size hint from untrusted input to preallocate more than this many values.
+const MAX_PREALLOCATED_LEN: usize = 4096;
+
+impl<T> Serialize for RotatedArraySet<T>
+where
+    T: Ord + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for chunk in self.chunks() {
+            for value in chunk {
+                seq.serialize_element(value)?;
+            }
+        }
+        seq.end()
+    }
+}
+
+impl<T> Serialize for RotatedArrayMultiset<T>
+where
+    T: Ord + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for chunk in self.chunks() {
+            for value in chunk {
+                seq.serialize_element(value)?;
+            }
+        }
+        seq.end()
+    }
+}
+
+impl<K, V> Serialize for RotatedArrayMap<K, V>
+where
+    K: Ord + Serialize,
+    V: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+// Reads a sequence into a vector, and returns whether the values are in ascending order
+// (strictly ascending if `strict` is true).
+fn read_seq<'de, A, T>(mut seq: A, strict: bool) -> Result<(Vec<T>, bool), A::Error>
+where
+    A: SeqAccess<'de>,
+    T: Ord + Deserialize<'de>,
+{
+    let capacity = min(seq.size_hint().unwrap_or(0), MAX_PREALLOCATED_LEN);
+    let mut values: Vec<T> = Vec::with_capacity(capacity);
+    let mut sorted = true;
+    while let Some(value) = seq.next_element()? {
+        if let Some(last) = values.last() {
+            sorted &= if strict {
+                *last < value
+            } else {
+                *last <= value
+            };
+        }
+        values.push(value);
+    }
+    Ok((values, sorted))
+}
+
+struct SetVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for SetVisitor<T>
+where
+    T: Ord + Deserialize<'de>,
+{
+    type Value = RotatedArraySet<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a sequence")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        let (values, sorted) = read_seq(seq, true)?;
+        if sorted {
+            Ok(RotatedArraySet::from_sorted_dedup_unchecked(values))
+        } else {
+            Ok(RotatedArraySet::from(values))
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for RotatedArraySet<T>
+where
+    T: Ord + Deserialize<'de>,
+{
+    /// Deserializes a set from a sequence. If the sequence contains equal values,
+    /// the first of them is kept.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(SetVisitor(PhantomData))
+    }
+}
+
+struct MultisetVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for MultisetVisitor<T>
+where
+    T: Ord + Deserialize<'de>,
+{
+    type Value = RotatedArrayMultiset<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a sequence")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        let (values, sorted) = read_seq(seq, false)?;
+        if sorted {
+            Ok(RotatedArrayMultiset::from_sorted_unchecked(values))
+        } else {
+            Ok(RotatedArrayMultiset::from(values))
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for RotatedArrayMultiset<T>
+where
+    T: Ord + Deserialize<'de>,
+{
+    /// Deserializes a multiset from a sequence. Equal values are kept in their order
+    /// in the sequence.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(MultisetVisitor(PhantomData))
+    }
+}
+
+struct MapVisitor<K, V>(PhantomData<(K, V)>);
+
+impl<'de, K, V> Visitor<'de> for MapVisitor<K, V>
+where
+    K: Ord + Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    type Value = RotatedArrayMap<K, V>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a map")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let capacity = min(map.size_hint().unwrap_or(0), MAX_PREALLOCATED_LEN);
+        let mut entries = Vec::with_capacity(capacity);
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        // the stable sort in `from_iter` takes linear time on entries in ascending key order
+        Ok(entries.into_iter().collect())
+    }
+}
+
+impl<'de, K, V> Deserialize<'de> for RotatedArrayMap<K, V>
+where
+    K: Ord + Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    /// Deserializes a map from a map. If a key occurs more than once, the value from
+    /// its last occurrence is kept.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(MapVisitor(PhantomData))
+    }
+}
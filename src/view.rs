@@ -0,0 +1,561 @@
+//! An on-disk format for `RotatedArraySet`, and a read-only view over it.
+//!
+//! The format stores the 2-level rotated array as-is, so writing a set never sorts it, and
+//! a [`RotatedArraySetView`] can answer queries directly from the bytes of a file (e.g., a
+//! memory-mapped file) without first loading it into a set. Values are encoded and decoded
+//! one at a time through the [`FixedWidth`] trait, so the bytes need no particular alignment.
+//!
+//! All integers are little-endian. A file consists of:
+//!
+//! | Offset | Size | Contents |
+//! |---|---|---|
+//! | 0 | 8 | the magic bytes `ROTARSET` |
+//! | 8 | 4 | the format version (currently 1) |
+//! | 12 | 4 | the width `w` of an encoded value, in bytes |
+//! | 16 | 8 | the number `n` of values |
+//! | 24 | 8 | the number `m` of subarrays |
+//! | 32 | `n * w` | the values, in the order of the array |
+//! | 32 + `n * w` | `m * 8` | the offset of the minimum within each subarray |
+//! | 32 + `n * w` + `m * 8` | 8 | the 64-bit FNV-1a hash of all preceding bytes |
+//!
+//! [`RotatedArraySetView`]: struct.RotatedArraySetView.html
+//! [`FixedWidth`]: trait.FixedWidth.html
+
+use core::cmp::Ordering::{self, Equal, Greater, Less};
+use core::cmp::{max, min};
+use core::convert::TryInto;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::ops::Bound::{Excluded, Included, Unbounded};
+use core::ops::RangeBounds;
+#[cfg(feature = "std")]
+use std::error::Error;
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+#[cfg(feature = "std")]
+use crate::RotatedArraySet;
+use crate::{get_array_idx_from_subarray_idx, get_subarray_idx_from_array_idx};
+
+const MAGIC: &[u8; 8] = b"ROTARSET";
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = 32;
+const CHECKSUM_LEN: usize = 8;
+const MIN_INDEX_WIDTH: usize = 8;
+// flush encoded bytes to the writer in blocks of about this size
+#[cfg(feature = "std")]
+const WRITE_BUFFER_LEN: usize = 8192;
+
+/// A plain-old-data value with a fixed-width little-endian encoding, which can be stored
+/// in the on-disk format and read back through a [`RotatedArraySetView`].
+///
+/// The encoding must preserve order: decoding the encodings of two values must give values
+/// that compare the same way.
+///
+/// [`RotatedArraySetView`]: struct.RotatedArraySetView.html
+pub trait FixedWidth: Ord + Copy {
+    /// The width of an encoded value, in bytes.
+    const WIDTH: usize;
+
+    /// Encodes the value into `bytes`, which is exactly `WIDTH` bytes long.
+    fn write_le(&self, bytes: &mut [u8]);
+
+    /// Decodes a value from `bytes`, which is exactly `WIDTH` bytes long.
+    fn read_le(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_fixed_width {
+    ($($t:ty),*) => {
+        $(
+            impl FixedWidth for $t {
+                const WIDTH: usize = core::mem::size_of::<$t>();
+
+                fn write_le(&self, bytes: &mut [u8]) {
+                    bytes.copy_from_slice(&self.to_le_bytes());
+                }
+
+                fn read_le(bytes: &[u8]) -> Self {
+                    <$t>::from_le_bytes(bytes.try_into().unwrap())
+                }
+            }
+        )*
+    };
+}
+
+impl_fixed_width!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// An error produced when bytes cannot be read as a [`RotatedArraySetView`].
+///
+/// [`RotatedArraySetView`]: struct.RotatedArraySetView.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatError {
+    /// The bytes don't start with the magic bytes of the format.
+    BadMagic,
+    /// The bytes are in a version of the format that isn't supported.
+    UnsupportedVersion(u32),
+    /// The width of the stored values doesn't match the width of the value type.
+    WidthMismatch {
+        /// The width of the value type.
+        expected: usize,
+        /// The width of the stored values.
+        found: usize,
+    },
+    /// The bytes are too short or too long for the header, or the header is inconsistent.
+    BadLength,
+    /// The stored checksum doesn't match the checksum of the bytes.
+    BadChecksum,
+    /// The offset of the minimum of a subarray is out of bounds.
+    BadMinIndex,
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::BadMagic => write!(f, "not a rotated array set"),
+            FormatError::UnsupportedVersion(version) => {
+                write!(f, "unsupported format version {}", version)
+            }
+            FormatError::WidthMismatch { expected, found } => write!(
+                f,
+                "values are {} bytes wide, but {} bytes were expected",
+                found, expected
+            ),
+            FormatError::BadLength => write!(f, "length doesn't match header"),
+            FormatError::BadChecksum => write!(f, "checksum mismatch"),
+            FormatError::BadMinIndex => write!(f, "subarray minimum out of bounds"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for FormatError {}
+
+// The 64-bit FNV-1a hash, which is simple and fast enough to detect corruption.
+#[derive(Debug, Clone, Copy)]
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+}
+
+// A writer that buffers its output and hashes it.
+#[cfg(feature = "std")]
+struct ChecksumWriter<'w, W: Write> {
+    writer: &'w mut W,
+    buffer: Vec<u8>,
+    hash: Fnv1a,
+}
+
+#[cfg(feature = "std")]
+impl<'w, W: Write> ChecksumWriter<'w, W> {
+    fn put(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.hash.update(bytes);
+        self.buffer.extend_from_slice(bytes);
+        if self.buffer.len() >= WRITE_BUFFER_LEN {
+            self.writer.write_all(&self.buffer)?;
+            self.buffer.clear();
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> io::Result<()> {
+        let checksum = self.hash.0.to_le_bytes();
+        self.buffer.extend_from_slice(&checksum);
+        self.writer.write_all(&self.buffer)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> RotatedArraySet<T>
+where
+    T: FixedWidth,
+{
+    /// Writes the set to `writer` in the on-disk format described in the [`view`] module,
+    /// which can be read back through a [`RotatedArraySetView`].
+    ///
+    /// The array is written as-is, without sorting it, in `O(n)` time. This method requires
+    /// the `std` feature, but views can be read without it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rotated_array_set::{RotatedArraySet, RotatedArraySetView};
+    ///
+    /// let set: RotatedArraySet<u32> = vec![1, 2, 3].into();
+    /// let mut bytes = Vec::new();
+    /// set.write_to(&mut bytes).unwrap();
+    ///
+    /// let view = RotatedArraySetView::<u32>::from_bytes(&bytes).unwrap();
+    /// assert!(view.iter().eq(set.iter().cloned()));
+    /// ```
+    ///
+    /// [`view`]: view/index.html
+    /// [`RotatedArraySetView`]: struct.RotatedArraySetView.html
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let mut out = ChecksumWriter {
+            writer,
+            buffer: Vec::with_capacity(WRITE_BUFFER_LEN + max(T::WIDTH, HEADER_LEN)),
+            hash: Fnv1a::new(),
+        };
+        out.put(MAGIC)?;
+        out.put(&VERSION.to_le_bytes())?;
+        out.put(&(T::WIDTH as u32).to_le_bytes())?;
+        out.put(&(self.data.len() as u64).to_le_bytes())?;
+        out.put(&(self.min_indexes.len() as u64).to_le_bytes())?;
+        let mut encoded = vec![0; T::WIDTH];
+        for value in &self.data {
+            value.write_le(&mut encoded);
+            out.put(&encoded)?;
+        }
+        for &min_index in &self.min_indexes {
+            out.put(&(min_index as u64).to_le_bytes())?;
+        }
+        out.finish()
+    }
+}
+
+/// A read-only view of a `RotatedArraySet` stored in the on-disk format described in the
+/// [`view`] module, which answers queries directly from the stored bytes.
+///
+/// Opening a view takes `O(√n)` time (plus `O(n)` time to verify the checksum, if requested),
+/// since nothing needs to be copied or sorted. Queries have the same complexity as
+/// on a `RotatedArraySet`, except that `rank` and `contains` take `O(lg n)` time.
+///
+/// # Examples
+///
+/// ```
+/// use rotated_array_set::{RotatedArraySet, RotatedArraySetView};
+///
+/// let set: RotatedArraySet<i64> = (0..100).map(|i| i * 2).collect();
+/// let mut bytes = Vec::new();
+/// set.write_to(&mut bytes).unwrap();
+///
+/// let view = RotatedArraySetView::<i64>::from_bytes(&bytes).unwrap();
+/// assert_eq!(view.len(), 100);
+/// assert!(view.contains(&42));
+/// assert_eq!(view.rank(&43), Err(22));
+/// assert_eq!(view.select(10), Some(20));
+/// assert_eq!(view.range(10..16).collect::<Vec<_>>(), [10, 12, 14]);
+/// ```
+///
+/// [`view`]: view/index.html
+#[derive(Debug)]
+pub struct RotatedArraySetView<'a, T> {
+    data: &'a [u8],
+    min_indexes: &'a [u8],
+    len: usize,
+    marker: PhantomData<T>,
+}
+
+// Derived `Copy` and `Clone` would require `T: Copy`, but we only hold references.
+impl<T> Clone for RotatedArraySetView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for RotatedArraySetView<'_, T> {}
+
+impl<'a, T> RotatedArraySetView<'a, T>
+where
+    T: FixedWidth,
+{
+    /// Opens a view over bytes in the on-disk format, after checking the header, the bounds
+    /// of the subarray minima, and the checksum.
+    ///
+    /// This takes `O(n)` time to verify the checksum.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a set of values of type `T`
+    /// in a supported version of the format, or are corrupt.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, FormatError> {
+        let view = Self::from_bytes_unverified(bytes)?;
+        let (contents, checksum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+        let mut hash = Fnv1a::new();
+        hash.update(contents);
+        if hash.0 != read_u64(checksum) {
+            return Err(FormatError::BadChecksum);
+        }
+        Ok(view)
+    }
+
+    /// Opens a view over bytes in the on-disk format, after checking the header and
+    /// the bounds of the subarray minima, but not the checksum.
+    ///
+    /// This takes `O(√n)` time. Queries on a view of corrupt bytes may give wrong results
+    /// or panic, but will not cause undefined behavior.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a set of values of type `T`
+    /// in a supported version of the format.
+    pub fn from_bytes_unverified(bytes: &'a [u8]) -> Result<Self, FormatError> {
+        if bytes.len() < HEADER_LEN + CHECKSUM_LEN {
+            return Err(FormatError::BadLength);
+        }
+        if &bytes[..8] != MAGIC {
+            return Err(FormatError::BadMagic);
+        }
+        let version = read_u32(&bytes[8..12]);
+        if version != VERSION {
+            return Err(FormatError::UnsupportedVersion(version));
+        }
+        let width = read_u32(&bytes[12..16]) as usize;
+        if width != T::WIDTH {
+            return Err(FormatError::WidthMismatch {
+                expected: T::WIDTH,
+                found: width,
+            });
+        }
+        let len = read_u64(&bytes[16..24])
+            .try_into()
+            .map_err(|_| FormatError::BadLength)?;
+        let subarray_count: usize = read_u64(&bytes[24..32])
+            .try_into()
+            .map_err(|_| FormatError::BadLength)?;
+        let expected_count = match len {
+            0 => 0,
+            len => get_subarray_idx_from_array_idx(len - 1) + 1,
+        };
+        if subarray_count != expected_count {
+            return Err(FormatError::BadLength);
+        }
+        let data_len = len.checked_mul(T::WIDTH).ok_or(FormatError::BadLength)?;
+        let min_indexes_len = subarray_count * MIN_INDEX_WIDTH;
+        let expected_len = data_len
+            .checked_add(HEADER_LEN + min_indexes_len + CHECKSUM_LEN)
+            .ok_or(FormatError::BadLength)?;
+        if bytes.len() != expected_len {
+            return Err(FormatError::BadLength);
+        }
+        let (data, rest) = bytes[HEADER_LEN..].split_at(data_len);
+        let view = RotatedArraySetView {
+            data,
+            min_indexes: &rest[..min_indexes_len],
+            len,
+            marker: PhantomData,
+        };
+        for subarray_idx in 0..subarray_count {
+            let (_, subarray_len) = view.subarray_bounds(subarray_idx);
+            if view.min_index(subarray_idx) >= subarray_len as u64 {
+                return Err(FormatError::BadMinIndex);
+            }
+        }
+        Ok(view)
+    }
+
+    /// Returns the number of values in the view.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the view contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the view contains the given value.
+    ///
+    /// This is an `O(lg n)` operation.
+    pub fn contains(&self, value: &T) -> bool {
+        self.rank(value).is_ok()
+    }
+
+    /// Returns the rank of the value in the view if it exists (as `Result::Ok`),
+    /// or the rank of its largest predecessor plus one, if it does not exist (as `Result::Err`).
+    ///
+    /// This is an `O(lg n)` operation.
+    pub fn rank(&self, value: &T) -> Result<usize, usize> {
+        self.rank_by(|x| x.cmp(value))
+    }
+
+    /// Returns the value in the view, if any, with the given rank.
+    ///
+    /// This is a constant-time operation.
+    pub fn select(&self, rank: usize) -> Option<T> {
+        if rank >= self.len {
+            return None;
+        }
+        let subarray_idx = get_subarray_idx_from_array_idx(rank);
+        let (start, subarray_len) = self.subarray_bounds(subarray_idx);
+        let min_index = self.min_index(subarray_idx) as usize;
+        let raw_idx = start + (min_index + rank - start) % subarray_len;
+        Some(T::read_le(
+            &self.data[raw_idx * T::WIDTH..(raw_idx + 1) * T::WIDTH],
+        ))
+    }
+
+    /// Gets a double-ended iterator that visits the values in the view in ascending order.
+    pub fn iter(&self) -> ViewIter<'a, T> {
+        ViewIter {
+            view: *self,
+            front: 0,
+            back: self.len,
+        }
+    }
+
+    /// Constructs a double-ended iterator over a sub-range of values in the view, which
+    /// may be specified as for [`RotatedArraySet::range`].
+    ///
+    /// This is an `O(lg n)` operation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// [`RotatedArraySet::range`]: struct.RotatedArraySet.html#method.range
+    pub fn range<R>(&self, range: R) -> ViewIter<'a, T>
+    where
+        R: RangeBounds<T>,
+    {
+        match (range.start_bound(), range.end_bound()) {
+            (Excluded(s), Excluded(e)) if s == e => {
+                panic!("range start and end are equal and excluded in RotatedArraySetView")
+            }
+            (Included(s), Included(e))
+            | (Included(s), Excluded(e))
+            | (Excluded(s), Included(e))
+            | (Excluded(s), Excluded(e))
+                if s > e =>
+            {
+                panic!("range start is greater than range end in RotatedArraySetView")
+            }
+            _ => {}
+        };
+        let front = match range.start_bound() {
+            Unbounded => 0,
+            Included(s) => self
+                .rank_by(|x| if x < s { Less } else { Greater })
+                .unwrap_err(),
+            Excluded(s) => self
+                .rank_by(|x| if x <= s { Less } else { Greater })
+                .unwrap_err(),
+        };
+        let back = match range.end_bound() {
+            Unbounded => self.len,
+            Included(e) => self
+                .rank_by(|x| if x <= e { Less } else { Greater })
+                .unwrap_err(),
+            Excluded(e) => self
+                .rank_by(|x| if x < e { Less } else { Greater })
+                .unwrap_err(),
+        };
+        ViewIter {
+            view: *self,
+            front,
+            back: max(front, back),
+        }
+    }
+
+    // Binary search over ranks, with the same comparator semantics as `slice::binary_search_by`.
+    fn rank_by<F>(&self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        let (mut lo, mut hi) = (0, self.len);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            match f(&self.select(mid).unwrap()) {
+                Less => lo = mid + 1,
+                Equal => return Ok(mid),
+                Greater => hi = mid,
+            }
+        }
+        Err(lo)
+    }
+
+    fn subarray_bounds(&self, subarray_idx: usize) -> (usize, usize) {
+        let start = get_array_idx_from_subarray_idx(subarray_idx);
+        (start, min(subarray_idx + 1, self.len - start))
+    }
+
+    fn min_index(&self, subarray_idx: usize) -> u64 {
+        let offset = subarray_idx * MIN_INDEX_WIDTH;
+        read_u64(&self.min_indexes[offset..offset + MIN_INDEX_WIDTH])
+    }
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes.try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes.try_into().unwrap())
+}
+
+/// An iterator over the values of a `RotatedArraySetView`.
+///
+/// This `struct` is created by the [`iter`] and [`range`] methods on
+/// [`RotatedArraySetView`]. See their documentation for more.
+///
+/// [`RotatedArraySetView`]: struct.RotatedArraySetView.html
+/// [`iter`]: struct.RotatedArraySetView.html#method.iter
+/// [`range`]: struct.RotatedArraySetView.html#method.range
+#[derive(Debug)]
+pub struct ViewIter<'a, T> {
+    view: RotatedArraySetView<'a, T>,
+    front: usize,
+    back: usize,
+}
+
+impl<T> Clone for ViewIter<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ViewIter<'_, T> {}
+
+impl<T> Iterator for ViewIter<'_, T>
+where
+    T: FixedWidth,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        self.view.select(self.front - 1)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<T> {
+        self.front = min(self.front.saturating_add(n), self.back);
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for ViewIter<'_, T>
+where
+    T: FixedWidth,
+{
+    fn next_back(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        self.view.select(self.back)
+    }
+}
+
+impl<T> ExactSizeIterator for ViewIter<'_, T> where T: FixedWidth {}
+
+impl<T> FusedIterator for ViewIter<'_, T> where T: FixedWidth {}
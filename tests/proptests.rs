@@ -1,9 +1,15 @@
 // adapted from https://github.com/ssomers/rust_bench_btreeset_intersection/blob/master/src/tests/set.rs
+// (kept close to the original, whose style newer clippy releases lint against)
+#![allow(clippy::while_let_on_iterator, clippy::legacy_numeric_constants)]
 extern crate proptest;
 use self::proptest::prelude::*;
-use rotated_array_set::RotatedArraySet;
-use std::cmp::min;
-use std::collections::BTreeSet;
+use rotated_array_set::{
+    subarray_count, ArrayRotatedSet, CapacityError, RotatedArrayMap, RotatedArrayMultiset,
+    RotatedArraySet, RotatedArraySetView,
+};
+use std::cmp::{max, min};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Bound;
 
 fn assert_difference<'a, I: Iterator<Item = &'a u8>>(
     mut it: I,
@@ -93,6 +99,60 @@ fn assert_union<'a, I: Iterator<Item = &'a u8>>(
     Ok(())
 }
 
+// an element ordered by `key` alone, so that we can tell which duplicate was kept
+#[derive(Debug, Clone)]
+struct Keyed {
+    key: u8,
+    tag: usize,
+}
+
+impl PartialEq for Keyed {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Keyed {}
+
+impl PartialOrd for Keyed {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Keyed {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+thread_local! {
+    // the number of comparisons `Fragile` allows before panicking
+    static COMPARISONS_LEFT: std::cell::Cell<usize> = const { std::cell::Cell::new(usize::MAX) };
+}
+
+// an element whose comparisons panic once the thread's budget of comparisons runs out
+#[derive(Debug, PartialEq, Eq)]
+struct Fragile(u8);
+
+impl PartialOrd for Fragile {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Fragile {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        COMPARISONS_LEFT.with(|left| {
+            if left.get() == 0 {
+                panic!("comparison budget exhausted");
+            }
+            left.set(left.get() - 1);
+        });
+        self.0.cmp(&other.0)
+    }
+}
+
 prop_compose! {
     fn arbitrary_instance()
                     (set: BTreeSet<u8>)
@@ -301,6 +361,49 @@ proptest! {
         assert_union(RotatedArraySet::union(&s1, &s2), &s1, &s2)?
     }
 
+    #[test]
+    fn set_operators(b1: BTreeSet<u8>, b2: BTreeSet<u8>) {
+        let s1: RotatedArraySet<u8> = b1.iter().cloned().collect();
+        let s2: RotatedArraySet<u8> = b2.iter().cloned().collect();
+        prop_assert!((&s1 - &s2).iter().eq((&b1 - &b2).iter()));
+        prop_assert!((&s1 ^ &s2).iter().eq((&b1 ^ &b2).iter()));
+        prop_assert!((&s1 & &s2).iter().eq((&b1 & &b2).iter()));
+        prop_assert!((&s1 | &s2).iter().eq((&b1 | &b2).iter()));
+    }
+
+    #[test]
+    fn unbalanced_set_operations(
+        small in prop::collection::btree_set(0u16..4096, 0..8),
+        start in 0u16..4096,
+        len in 0u16..4096,
+    ) {
+        let large: BTreeSet<u16> = (start..start.saturating_add(len)).collect();
+        let s1: RotatedArraySet<u16> = small.iter().cloned().collect();
+        let s2: RotatedArraySet<u16> = large.iter().cloned().collect();
+        for (a, b, r1, r2) in [(&small, &large, &s1, &s2), (&large, &small, &s2, &s1)] {
+            prop_assert!(r1.difference(r2).eq(a.difference(b)));
+            prop_assert!(r1.symmetric_difference(r2).eq(a.symmetric_difference(b)));
+            prop_assert!(r1.intersection(r2).eq(a.intersection(b)));
+            prop_assert!(r1.union(r2).eq(a.union(b)));
+            prop_assert_eq!(r1.is_disjoint(r2), a.is_disjoint(b));
+            prop_assert_eq!(r1.is_subset(r2), a.is_subset(b));
+            prop_assert_eq!(r1.is_superset(r2), a.is_superset(b));
+            let (lower, upper) = r1.union(r2).size_hint();
+            let count = a.union(b).count();
+            prop_assert!(lower <= count && upper.map_or(true, |upper| count <= upper));
+        }
+    }
+
+    #[test]
+    fn compare_sets(b1: BTreeSet<u8>, b2: BTreeSet<u8>, n in 0usize..256) {
+        let s1: RotatedArraySet<u8> = b1.iter().cloned().collect();
+        let s2: RotatedArraySet<u8> = b2.iter().cloned().collect();
+        let prefix: RotatedArraySet<u8> = b1.iter().take(n).cloned().collect();
+        prop_assert_eq!(s1.cmp(&s2), b1.cmp(&b2));
+        prop_assert_eq!(s1.partial_cmp(&s2), b1.partial_cmp(&b2));
+        prop_assert_eq!(prefix.cmp(&s1), min(n, b1.len()).cmp(&b1.len()));
+    }
+
     #[test]
     fn insert_remove(mut s in arbitrary_instance(), v: u8) {
         let c = s.contains(&v);
@@ -316,6 +419,42 @@ proptest! {
         prop_assert_eq!(v1, v2);
     }
 
+    #[test]
+    fn remove_at(ops in prop::collection::vec((any::<bool>(), any::<u8>()), 0..512)) {
+        let mut s = RotatedArraySet::new();
+        let mut model: Vec<u8> = Vec::new();
+        for (insert, v) in ops {
+            if insert {
+                if let Err(pos) = model.binary_search(&v) {
+                    model.insert(pos, v);
+                }
+                s.insert(v);
+            } else {
+                // occasionally probe one past the end
+                let rank = v as usize % (model.len() + 1);
+                let expected = if rank < model.len() { Some(model.remove(rank)) } else { None };
+                prop_assert_eq!(s.remove_at(rank), expected);
+            }
+        }
+        prop_assert!(s.iter().eq(model.iter()));
+    }
+
+    #[test]
+    fn double_ended_queue(ops in prop::collection::vec((0u8..4, any::<u8>()), 0..512)) {
+        let mut s = RotatedArraySet::new();
+        let mut model = BTreeSet::new();
+        for (op, v) in ops {
+            match op {
+                0 | 1 => prop_assert_eq!(s.insert(v), model.insert(v)),
+                2 => prop_assert_eq!(s.pop_first(), model.pop_first()),
+                _ => prop_assert_eq!(s.pop_last(), model.pop_last()),
+            }
+            prop_assert_eq!(s.first(), model.first());
+            prop_assert_eq!(s.last(), model.last());
+        }
+        prop_assert!(s.iter().eq(model.iter()));
+    }
+
     #[test]
     fn compare_iter(s in arbitrary_instance()) {
         let iter = s.iter();
@@ -356,4 +495,753 @@ proptest! {
         iter_mut.next_back();
         prop_assert!(iter_mut.next().is_none());
     }
+
+    #[test]
+    fn insert_remove_owned(ops in prop::collection::vec((any::<bool>(), any::<u8>()), 0..512)) {
+        let mut s = RotatedArraySet::new();
+        let mut model = BTreeSet::new();
+        for (insert, v) in ops {
+            let v = v.to_string();
+            if insert {
+                prop_assert_eq!(s.insert(v.clone()), model.insert(v));
+            } else {
+                prop_assert_eq!(s.take(v.as_str()), model.take(v.as_str()));
+            }
+        }
+        prop_assert_eq!(s.len(), model.len());
+        prop_assert!(s.iter().eq(model.iter()));
+        prop_assert!(s.into_iter().eq(model.into_iter()));
+    }
+
+    #[test]
+    fn compare_range(values: Vec<u8>, start: u8, end: u8) {
+        // build by insertion, so that subarrays are rotated
+        let mut s = RotatedArraySet::new();
+        for &v in &values {
+            s.insert(v);
+        }
+        let model: BTreeSet<_> = values.into_iter().collect();
+        let (start, end) = (min(start, end), max(start, end));
+        prop_assert!(s.range(start..end).eq(model.range(start..end)));
+        prop_assert!(s.range(start..=end).eq(model.range(start..=end)));
+        prop_assert!(s.range(..end).rev().eq(model.range(..end).rev()));
+        prop_assert!(s.range(start..).eq(model.range(start..)));
+    }
+
+    #[test]
+    fn compare_map(ops in prop::collection::vec((0u8..3, any::<u8>(), any::<u16>()), 0..512),
+                   start: u8, end: u8) {
+        let mut m = RotatedArrayMap::new();
+        let mut model = BTreeMap::new();
+        for (op, k, v) in ops {
+            match op {
+                0 => prop_assert_eq!(m.insert(k, v), model.insert(k, v)),
+                1 => prop_assert_eq!(m.remove(&k), model.remove(&k)),
+                _ => {
+                    if let Some(value) = m.get_mut(&k) {
+                        *value = value.wrapping_add(v);
+                    }
+                    if let Some(value) = model.get_mut(&k) {
+                        *value = value.wrapping_add(v);
+                    }
+                }
+            }
+        }
+        prop_assert_eq!(m.len(), model.len());
+        prop_assert!(m.iter().eq(model.iter()));
+        for (i, (k, v)) in model.iter().enumerate() {
+            prop_assert_eq!(m.rank(k), Ok(i));
+            prop_assert_eq!(m.select(i), Some((k, v)));
+        }
+        let (start, end) = (min(start, end), max(start, end));
+        prop_assert!(m.range(start..end).eq(model.range(start..end)));
+        for ((_, v), (_, model_v)) in m.range_mut(start..=end).zip(model.range_mut(start..=end)) {
+            *v = v.wrapping_mul(3);
+            *model_v = model_v.wrapping_mul(3);
+        }
+        prop_assert!(m.iter().rev().eq(model.iter().rev()));
+        for v in m.values_mut() {
+            *v = v.wrapping_add(1);
+        }
+        for v in model.values_mut() {
+            *v = v.wrapping_add(1);
+        }
+        let mut iter = m.clone().into_iter();
+        prop_assert_eq!(iter.len(), model.len());
+        prop_assert_eq!(iter.next_back(), model.iter().next_back().map(|(&k, &v)| (k, v)));
+        prop_assert!(m.into_iter().rev().eq(model.clone().into_iter().rev()));
+        prop_assert!(iter.eq(model.into_iter().rev().skip(1).rev()));
+    }
+
+    #[test]
+    fn compare_multiset(ops in prop::collection::vec((0u8..4, 0u8..32), 0..512),
+                        start: u8, end: u8) {
+        let mut s = RotatedArrayMultiset::new();
+        let mut model: Vec<u8> = Vec::new();
+        for (op, v) in ops {
+            match op {
+                0 | 1 => {
+                    s.insert(v);
+                    let pos = model.iter().take_while(|&&x| x <= v).count();
+                    model.insert(pos, v);
+                }
+                2 => {
+                    let pos = model.iter().position(|&x| x == v);
+                    prop_assert_eq!(s.remove_one(&v), pos.map(|pos| model.remove(pos)));
+                }
+                _ => {
+                    let count = model.iter().filter(|&&x| x == v).count();
+                    model.retain(|&x| x != v);
+                    prop_assert_eq!(s.remove_all(&v), count);
+                }
+            }
+        }
+        prop_assert_eq!(s.len(), model.len());
+        prop_assert!(s.iter().eq(model.iter()));
+        prop_assert!(s.iter().rev().eq(model.iter().rev()));
+        for v in 0u8..33 {
+            let first = model.iter().take_while(|&&x| x < v).count();
+            let last = model.iter().take_while(|&&x| x <= v).count();
+            prop_assert_eq!(s.count(&v), last - first);
+            prop_assert!(s.equal_range(&v).eq(model[first..last].iter()));
+            if first < last {
+                prop_assert_eq!(s.rank_first(&v), Ok(first));
+                prop_assert_eq!(s.rank_last(&v), Ok(last - 1));
+            } else {
+                prop_assert_eq!(s.rank_first(&v), Err(first));
+                prop_assert_eq!(s.rank_last(&v), Err(first));
+            }
+        }
+        let (start, end) = (start % 33, end % 33);
+        let (start, end) = (min(start, end), max(start, end));
+        prop_assert!(s.range(start..=end).eq(model.iter().filter(|&&x| start <= x && x <= end)));
+        prop_assert!(s.into_iter().eq(model.into_iter()));
+    }
+
+    #[test]
+    fn compare_array_set(ops in prop::collection::vec((0u8..4, 0u8..128), 0..512),
+                         other: BTreeSet<u8>, start: u8, end: u8) {
+        let mut s: ArrayRotatedSet<u8, 64, { subarray_count(64) }> = ArrayRotatedSet::new();
+        let mut model = BTreeSet::new();
+        for (op, v) in ops {
+            match op {
+                0 | 1 => {
+                    let expected = if model.contains(&v) {
+                        Ok(false)
+                    } else if model.len() == 64 {
+                        Err(CapacityError(v))
+                    } else {
+                        Ok(model.insert(v))
+                    };
+                    prop_assert_eq!(s.try_insert(v), expected);
+                }
+                2 => prop_assert_eq!(s.remove(&v), model.remove(&v)),
+                _ => {
+                    let rank = usize::from(v) % 65;
+                    let expected = model.iter().nth(rank).cloned();
+                    if let Some(value) = expected {
+                        model.remove(&value);
+                    }
+                    prop_assert_eq!(s.remove_at(rank), expected);
+                }
+            }
+            prop_assert_eq!(s.is_full(), model.len() == 64);
+        }
+        prop_assert_eq!(s.len(), model.len());
+        prop_assert!(s.iter().eq(model.iter()));
+        prop_assert!(s.iter().rev().eq(model.iter().rev()));
+        prop_assert!(s.chunks().flatten().eq(model.iter()));
+        for (rank, v) in model.iter().enumerate() {
+            prop_assert_eq!(s.rank(v), Ok(rank));
+            prop_assert_eq!(s.select(rank), Some(v));
+        }
+        let (start, end) = (min(start, end), max(start, end));
+        prop_assert!(s.range(start..end).eq(model.range(start..end)));
+        let mut t: ArrayRotatedSet<u8, 256, 23> = ArrayRotatedSet::new();
+        for &v in &other {
+            prop_assert_eq!(t.try_insert(v), Ok(true));
+        }
+        prop_assert!(s.difference(&t).eq(model.difference(&other)));
+        prop_assert!(s.symmetric_difference(&t).eq(model.symmetric_difference(&other)));
+        prop_assert!(s.intersection(&t).eq(model.intersection(&other)));
+        prop_assert!(s.union(&t).eq(model.union(&other)));
+        prop_assert_eq!(s.is_subset(&t), model.is_subset(&other));
+        prop_assert_eq!(s.is_disjoint(&t), model.is_disjoint(&other));
+        prop_assert_eq!(s.floor(&start), model.range(..=start).next_back());
+        prop_assert_eq!(s.ceiling(&start), model.range(start..).next());
+        let u: ArrayRotatedSet<u8, 256, 23> = s.iter().cloned().collect();
+        prop_assert!((&u - &t).iter().eq(model.difference(&other)));
+        prop_assert!((&u ^ &t).iter().eq(model.symmetric_difference(&other)));
+        prop_assert!((&u & &t).iter().eq(model.intersection(&other)));
+        prop_assert!((&u | &t).iter().eq(model.union(&other)));
+        prop_assert!(u.clone().into_iter().eq(model.iter().cloned()));
+        prop_assert!(u.into_iter().rev().eq(model.iter().rev().cloned()));
+        let mut r = s.clone();
+        r.retain(|&v| v % 3 != 0);
+        prop_assert!(r.iter().eq(model.iter().filter(|&&v| v % 3 != 0)));
+        let mut e = s.clone();
+        let mut expected = model.clone();
+        let mut result = Ok(());
+        for &v in &other {
+            if !expected.contains(&v) && expected.len() == 64 {
+                result = Err(CapacityError(v));
+                break;
+            }
+            expected.insert(v);
+        }
+        prop_assert_eq!(e.try_extend(other.iter().cloned()), result);
+        prop_assert!(e.iter().eq(expected.iter()));
+        while let Some(v) = s.pop_last() {
+            prop_assert_eq!(Some(v), model.iter().next_back().cloned());
+            model.remove(&v);
+        }
+    }
+
+    #[test]
+    fn append_split_off(keys1 in prop::collection::vec(0u8..64, 0..128), keys2 in prop::collection::vec(0u8..64, 0..128), split: u8) {
+        // insert one at a time, so that subarrays are rotated
+        let mut s1 = RotatedArraySet::new();
+        for (tag, &key) in keys1.iter().enumerate() {
+            s1.insert(Keyed { key, tag });
+        }
+        let mut s2 = RotatedArraySet::new();
+        for (tag, &key) in keys2.iter().enumerate() {
+            s2.insert(Keyed { key, tag: keys1.len() + tag });
+        }
+        // values already in `s1` are kept, like `BTreeSet::insert`
+        let mut model: BTreeSet<Keyed> = s1.iter().cloned().collect();
+        for v in &s2 {
+            model.insert(v.clone());
+        }
+        s1.append(&mut s2);
+        prop_assert!(s2.is_empty());
+        let tags = |s: &RotatedArraySet<Keyed>| s.iter().map(|v| (v.key, v.tag)).collect::<Vec<_>>();
+        prop_assert_eq!(tags(&s1), model.iter().map(|v| (v.key, v.tag)).collect::<Vec<_>>());
+        let split = Keyed { key: split, tag: 0 };
+        let tail = s1.split_off(&split);
+        let model_tail = model.split_off(&split);
+        prop_assert_eq!(tags(&s1), model.iter().map(|v| (v.key, v.tag)).collect::<Vec<_>>());
+        prop_assert_eq!(tags(&tail), model_tail.iter().map(|v| (v.key, v.tag)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn split_concat_by_rank(values: Vec<u8>, rank in 0usize..257) {
+        let mut s = RotatedArraySet::new();
+        for v in &values {
+            s.insert(*v);
+        }
+        let model: Vec<u8> = s.iter().cloned().collect();
+        let rank = min(rank, model.len());
+        let (head, tail) = s.split_at_rank(rank);
+        prop_assert!(head.eq(model[..rank].iter()));
+        prop_assert!(tail.eq(model[rank..].iter()));
+        let tail = s.split_off_at(rank);
+        prop_assert!(s.iter().eq(model[..rank].iter()));
+        prop_assert!(tail.iter().eq(model[rank..].iter()));
+        let s = s.concat_ordered(tail);
+        prop_assert!(s.iter().eq(model.iter()));
+        for (i, v) in model.iter().enumerate() {
+            prop_assert_eq!(s.rank(v), Ok(i));
+        }
+    }
+
+    #[test]
+    fn order_statistics(values: Vec<u8>, q in 0.0f64..=1.0, start: u8, end: u8, n in 0usize..64, v: u8) {
+        let (start, end) = (min(start, end), max(start, end));
+        let mut sorted = values.clone();
+        sorted.sort();
+        let multiset: RotatedArrayMultiset<u8> = values.iter().cloned().collect();
+        let set: RotatedArraySet<u8> = values.iter().cloned().collect();
+        let mut deduped = sorted.clone();
+        deduped.dedup();
+        for (model, quantile, median, percentile_rank, count_range, nth_in_range) in [
+            (
+                &sorted,
+                multiset.quantile(q),
+                multiset.median(),
+                multiset.percentile_rank(&v),
+                multiset.count_range(start..end),
+                multiset.nth_in_range(start..=end, n),
+            ),
+            (
+                &deduped,
+                set.quantile(q),
+                set.median(),
+                set.percentile_rank(&v),
+                set.count_range(start..end),
+                set.nth_in_range(start..=end, n),
+            ),
+        ] {
+            let len = model.len();
+            let expected_quantile = model.get((q * len.saturating_sub(1) as f64) as usize);
+            prop_assert_eq!(quantile, expected_quantile);
+            prop_assert_eq!(median, model.get(len.saturating_sub(1) / 2));
+            let less = model.iter().filter(|&&x| x < v).count();
+            let equal = model.iter().filter(|&&x| x == v).count();
+            let expected_rank = if len == 0 { 0.0 } else { (less as f64 + equal as f64 / 2.0) * 100.0 / len as f64 };
+            prop_assert_eq!(percentile_rank, expected_rank);
+            prop_assert_eq!(count_range, model.iter().filter(|&&x| start <= x && x < end).count());
+            prop_assert_eq!(nth_in_range, model.iter().filter(|&&x| start <= x && x <= end).nth(n));
+        }
+    }
+
+    #[test]
+    fn neighbors(values: Vec<u8>, v: u8) {
+        let mut s = RotatedArraySet::new();
+        for x in &values {
+            s.insert(*x);
+        }
+        let model: BTreeSet<u8> = values.iter().cloned().collect();
+        prop_assert_eq!(s.floor(&v), model.range(..=v).next_back());
+        prop_assert_eq!(s.ceiling(&v), model.range(v..).next());
+        prop_assert_eq!(s.strictly_below(&v), model.range(..v).next_back());
+        prop_assert_eq!(s.strictly_above(&v), model.range((Bound::Excluded(v), Bound::Unbounded)).next());
+        let distance = |x: &u8, y: &u8| i32::from(*x) - i32::from(*y);
+        let expected = model.iter().min_by_key(|&y| distance(&v, y).abs());
+        prop_assert_eq!(s.nearest(&v, |x, y| distance(x, y).abs()), expected);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn random_sampling(values: BTreeSet<u8>, seed: u64, amount in 0usize..300, start: u8, end: u8) {
+        use rand::{distributions::Distribution, rngs::StdRng, SeedableRng};
+        let (start, end) = (min(start, end), max(start, end));
+        let mut rng = StdRng::seed_from_u64(seed);
+        let s: RotatedArraySet<u8> = values.iter().cloned().collect();
+        prop_assert_eq!(s.choose(&mut rng).is_some(), !values.is_empty());
+        prop_assert!(s.choose(&mut rng).map_or(true, |v| values.contains(v)));
+        let mut sample: Vec<_> = s.choose_multiple(&mut rng, amount).collect();
+        prop_assert_eq!(sample.len(), min(amount, values.len()));
+        sample.sort();
+        sample.dedup();
+        prop_assert_eq!(sample.len(), min(amount, values.len()));
+        prop_assert!(sample.iter().all(|v| values.contains(v)));
+        match s.choose_in_range(start..end, &mut rng) {
+            Some(v) => prop_assert!(start <= *v && *v < end && values.contains(v)),
+            None => prop_assert_eq!(values.range(start..end).next(), None),
+        }
+        let mut shuffled: Vec<_> = s.shuffle_iter(&mut rng).cloned().collect();
+        shuffled.sort();
+        prop_assert!(shuffled.iter().eq(values.iter()));
+        // only values from `start` onward have nonzero weight
+        let weight = |&v: &u8| if v < start { 0u32 } else { u32::from(v) + 1 };
+        match s.choose_weighted(&mut rng, weight) {
+            Ok(v) => prop_assert!(start <= *v && values.contains(v)),
+            Err(_) => prop_assert_eq!(values.range(start..).next(), None),
+        }
+        if let Ok(sampler) = s.weighted_sampler(weight) {
+            for v in sampler.sample_iter(&mut rng).take(amount) {
+                prop_assert!(start <= *v && values.contains(v));
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip(values: Vec<u8>, keys in prop::collection::vec(0u8..32, 0..64)) {
+        let mut set = RotatedArraySet::new();
+        let mut multiset = RotatedArrayMultiset::new();
+        for v in &values {
+            set.insert(*v);
+            multiset.insert(*v);
+        }
+        let json = serde_json::to_string(&set).unwrap();
+        prop_assert_eq!(&json, &serde_json::to_string(&set.iter().collect::<Vec<_>>()).unwrap());
+        prop_assert_eq!(serde_json::from_str::<RotatedArraySet<u8>>(&json).unwrap(), set.clone());
+        let json = serde_json::to_string(&multiset).unwrap();
+        prop_assert_eq!(serde_json::from_str::<RotatedArrayMultiset<u8>>(&json).unwrap(), multiset.clone());
+        // unsorted input is sorted, and duplicates are handled as by `collect`
+        let json = serde_json::to_string(&values).unwrap();
+        prop_assert_eq!(serde_json::from_str::<RotatedArraySet<u8>>(&json).unwrap(), set);
+        prop_assert_eq!(serde_json::from_str::<RotatedArrayMultiset<u8>>(&json).unwrap(), multiset);
+        let map: RotatedArrayMap<u8, usize> = keys.iter().enumerate().map(|(i, &k)| (k, i)).collect();
+        let json = serde_json::to_string(&map).unwrap();
+        let model: BTreeMap<u8, usize> = keys.iter().enumerate().map(|(i, &k)| (k, i)).collect();
+        prop_assert_eq!(&json, &serde_json::to_string(&model).unwrap());
+        let map: RotatedArrayMap<u8, usize> = serde_json::from_str(&json).unwrap();
+        prop_assert!(map.iter().eq(model.iter()));
+    }
+
+    #[test]
+    fn view_round_trip(values: Vec<i16>, probe: i16, start: i16, end: i16, corrupt: usize) {
+        let (start, end) = (min(start, end), max(start, end));
+        let mut set = RotatedArraySet::new();
+        for v in &values {
+            set.insert(*v);
+        }
+        let mut bytes = Vec::new();
+        set.write_to(&mut bytes).unwrap();
+        let view = RotatedArraySetView::<i16>::from_bytes(&bytes).unwrap();
+        prop_assert_eq!(view.len(), set.len());
+        prop_assert!(view.iter().eq(set.iter().cloned()));
+        prop_assert!(view.iter().rev().eq(set.iter().rev().cloned()));
+        prop_assert!(view.range(start..end).eq(set.range(start..end).cloned()));
+        prop_assert!(view.range(start..=end).eq(set.range(start..=end).cloned()));
+        prop_assert_eq!(view.rank(&probe), set.rank(&probe));
+        prop_assert_eq!(view.contains(&probe), set.contains(&probe));
+        for rank in 0..=set.len() {
+            prop_assert_eq!(view.select(rank), set.select(rank).cloned());
+        }
+        // any single corrupted byte is detected
+        let corrupt = corrupt % bytes.len();
+        bytes[corrupt] ^= 0x5a;
+        prop_assert!(RotatedArraySetView::<i16>::from_bytes(&bytes).is_err());
+        prop_assert!(RotatedArraySetView::<u32>::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn bulk_constructors_keep_first(keys in prop::collection::vec(0u8..32, 0..256)) {
+        let values: Vec<_> = keys
+            .into_iter()
+            .enumerate()
+            .map(|(tag, key)| Keyed { key, tag })
+            .collect();
+        // `BTreeSet::insert` never replaces an existing element
+        let mut model = BTreeSet::new();
+        for v in &values {
+            model.insert(v.clone());
+        }
+        let tags = |s: &RotatedArraySet<Keyed>| s.iter().map(|v| (v.key, v.tag)).collect::<Vec<_>>();
+        let model_tags: Vec<_> = model.iter().map(|v| (v.key, v.tag)).collect();
+        let from_slice = RotatedArraySet::from(values.as_slice());
+        prop_assert_eq!(tags(&from_slice), model_tags.clone());
+        let from_iter: RotatedArraySet<_> = values.iter().cloned().collect();
+        prop_assert_eq!(tags(&from_iter), model_tags.clone());
+        let try_from_vec = RotatedArraySet::try_from_vec(values.clone()).unwrap();
+        prop_assert_eq!(tags(&try_from_vec), model_tags.clone());
+        let from_vec = RotatedArraySet::from(values);
+        prop_assert_eq!(tags(&from_vec), model_tags);
+        let sorted: Vec<_> = model.into_iter().collect();
+        let from_sorted = RotatedArraySet::try_from_sorted(sorted.clone());
+        prop_assert_eq!(from_sorted, Ok(from_vec));
+        if sorted.len() > 1 {
+            let mut unsorted = sorted;
+            unsorted.swap(0, 1);
+            prop_assert!(RotatedArraySet::try_from_sorted(unsorted).is_err());
+        }
+    }
+
+    #[test]
+    fn retain_extract_if(values: Vec<u8>, modulus in 1u8..8, take in 0usize..64) {
+        // build by insertion, so that subarrays are rotated
+        let mut s = RotatedArraySet::new();
+        for &v in &values {
+            s.insert(v);
+        }
+        let model: BTreeSet<_> = values.into_iter().collect();
+        let mut retained = s.clone();
+        retained.retain(|&v| v % modulus != 0);
+        prop_assert!(retained.iter().eq(model.iter().filter(|&&v| v % modulus != 0)));
+        // only consume part of the iterator, so that unvisited values are retained
+        let extracted: Vec<_> = s.extract_if(|&v| v % modulus == 0).take(take).collect();
+        let expected: Vec<_> = model.iter().cloned().filter(|&v| v % modulus == 0).take(take).collect();
+        prop_assert_eq!(&extracted, &expected);
+        prop_assert!(s.iter().eq(model.iter().filter(|v| !expected.contains(v))));
+        s.insert(modulus);
+        prop_assert!(s.contains(&modulus));
+    }
+
+    #[test]
+    fn panicking_retain(values: BTreeSet<u8>, budget in 0usize..128) {
+        // insert in descending order, so that subarrays are rotated
+        let mut s = RotatedArraySet::new();
+        for &v in values.iter().rev() {
+            s.insert(v);
+        }
+        let mut left = budget;
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            s.retain(|&v| {
+                assert!(left > 0);
+                left -= 1;
+                v % 2 == 0
+            })
+        }));
+        // the values visited before the panic are filtered, and the rest are kept
+        let visited = if result.is_err() { budget } else { values.len() };
+        let mut model: BTreeSet<u8> = values
+            .iter()
+            .enumerate()
+            .filter(|&(i, &v)| i >= visited || v % 2 == 0)
+            .map(|(_, &v)| v)
+            .collect();
+        prop_assert!(s.iter().eq(model.iter()));
+        for v in 0..=255 {
+            prop_assert_eq!(s.insert(v), model.insert(v));
+        }
+        prop_assert!(s.iter().eq(model.iter()));
+    }
+
+    #[test]
+    fn map_panicking_retain(keys: BTreeSet<u8>, budget in 0usize..128) {
+        let mut m: RotatedArrayMap<u8, usize> = RotatedArrayMap::new();
+        for &k in keys.iter().rev() {
+            m.insert(k, usize::from(k));
+        }
+        let mut left = budget;
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            m.retain(|&k, v| {
+                assert!(left > 0);
+                left -= 1;
+                *v += 1;
+                k % 2 == 0
+            })
+        }));
+        // the entries visited before the panic are filtered and updated, and the rest are kept
+        let visited = if result.is_err() { budget } else { keys.len() };
+        let mut model: BTreeMap<u8, usize> = keys
+            .iter()
+            .enumerate()
+            .filter(|&(i, &k)| i >= visited || k % 2 == 0)
+            .map(|(i, &k)| (k, usize::from(k) + usize::from(i < visited)))
+            .collect();
+        prop_assert!(m.iter().eq(model.iter()));
+        for k in 0..=255 {
+            prop_assert_eq!(m.insert(k, 0), model.insert(k, 0));
+        }
+        prop_assert!(m.iter().eq(model.iter()));
+    }
+
+    #[test]
+    fn array_set_panicking_retain(values: BTreeSet<u8>, budget in 0usize..128) {
+        let mut s: ArrayRotatedSet<u8, 256, 23> = values.iter().cloned().collect();
+        let mut left = budget;
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            s.retain(|&v| {
+                assert!(left > 0);
+                left -= 1;
+                v % 2 == 0
+            })
+        }));
+        // the values visited before the panic are filtered, and the rest are kept
+        let visited = if result.is_err() { budget } else { values.len() };
+        let expected = values
+            .iter()
+            .enumerate()
+            .filter(|&(i, &v)| i >= visited || v % 2 == 0)
+            .map(|(_, v)| v);
+        prop_assert!(s.iter().eq(expected));
+        s.insert(255);
+        prop_assert!(s.contains(&255));
+    }
+
+    #[test]
+    fn append_panicking_cmp(a: BTreeSet<u8>, b: BTreeSet<u8>, budget in 0usize..512) {
+        let mut s1: RotatedArraySet<_> = a.iter().map(|&v| Fragile(v)).collect();
+        let mut s2: RotatedArraySet<_> = b.iter().map(|&v| Fragile(v)).collect();
+        COMPARISONS_LEFT.with(|left| left.set(budget));
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| s1.append(&mut s2)));
+        COMPARISONS_LEFT.with(|left| left.set(usize::MAX));
+        if result.is_err() {
+            // a panicking comparison leaves both sets valid, though possibly emptied
+            prop_assert!(s1.iter().all(|v| a.contains(&v.0) || b.contains(&v.0)));
+            prop_assert!(s1.iter().zip(s1.iter().skip(1)).all(|(x, y)| x < y));
+        } else {
+            prop_assert!(s1.iter().map(|v| v.0).eq(a.union(&b).cloned()));
+        }
+        s1.insert(Fragile(0));
+        s2.insert(Fragile(0));
+        prop_assert!(s1.contains(&Fragile(0)) && s2.contains(&Fragile(0)));
+    }
+
+    #[test]
+    fn leaked_extract_if(values: Vec<u8>, value: u8) {
+        let mut s = RotatedArraySet::new();
+        for &v in &values {
+            s.insert(v);
+        }
+        // a leaked iterator leaves an empty set behind
+        std::mem::forget(s.extract_if(|&v| v % 2 == 0));
+        prop_assert!(s.is_empty());
+        s.insert(value);
+        prop_assert!(s.iter().eq([value].iter()));
+    }
+
+    #[test]
+    fn drain(values: Vec<u8>, start: u8, end: u8, rank_start in 0usize..256, rank_end in 0usize..256) {
+        // build by insertion, so that subarrays are rotated
+        let mut s = RotatedArraySet::new();
+        for &v in &values {
+            s.insert(v);
+        }
+        let mut model: BTreeSet<_> = values.into_iter().collect();
+        let mut ranked = s.clone();
+        let (start, end) = (min(start, end), max(start, end));
+        let drained: Vec<_> = s.drain(start..end).collect();
+        let expected: Vec<_> = model.range(start..end).cloned().collect();
+        prop_assert_eq!(&drained, &expected);
+        for v in expected {
+            model.remove(&v);
+        }
+        prop_assert!(s.iter().eq(model.iter()));
+        s.insert(start);
+        prop_assert!(s.contains(&start));
+
+        let mut model: Vec<_> = ranked.iter().cloned().collect();
+        let (rank_start, rank_end) = (min(rank_start, rank_end), max(rank_start, rank_end));
+        let (rank_start, rank_end) = (min(rank_start, model.len()), min(rank_end, model.len()));
+        prop_assert!(ranked.drain_ranks(rank_start..rank_end).eq(model.drain(rank_start..rank_end)));
+        prop_assert!(ranked.iter().eq(model.iter()));
+        prop_assert_eq!(ranked.pop_last(), model.pop());
+    }
+
+    #[test]
+    fn leaked_drain(values: Vec<u8>, rank_start in 0usize..256, rank_end in 0usize..256, value: u8) {
+        let mut s = RotatedArraySet::new();
+        for &v in &values {
+            s.insert(v);
+        }
+        let model: Vec<_> = s.iter().cloned().collect();
+        let (rank_start, rank_end) = (min(rank_start, rank_end), max(rank_start, rank_end));
+        let (rank_start, rank_end) = (min(rank_start, model.len()), min(rank_end, model.len()));
+        // a leaked iterator over a nonempty range leaves only the values before the range behind
+        std::mem::forget(s.drain_ranks(rank_start..rank_end));
+        let kept = if rank_start < rank_end { rank_start } else { model.len() };
+        prop_assert!(s.iter().eq(model[..kept].iter()));
+        let mut model: BTreeSet<_> = model[..kept].iter().cloned().collect();
+        s.insert(value);
+        model.insert(value);
+        prop_assert!(s.iter().eq(model.iter()));
+    }
+
+    #[test]
+    fn extend(initial in prop::collection::vec(0u8..64, 0..256),
+              batch in prop::collection::vec(0u8..64, 0..256),
+              sorted: bool) {
+        let tag = |(tag, key)| Keyed { key, tag };
+        // build by insertion, so that subarrays are rotated
+        let mut s = RotatedArraySet::new();
+        let mut model = BTreeSet::new();
+        let mut multiset = RotatedArrayMultiset::new();
+        let mut multiset_model = Vec::new();
+        for v in initial.into_iter().enumerate().map(tag) {
+            s.insert(v.clone());
+            model.insert(v.clone());
+            multiset.insert(v.key);
+            multiset_model.push(v.key);
+        }
+        let mut batch: Vec<_> = batch.into_iter().enumerate().map(|(i, k)| (i + 256, k)).map(tag).collect();
+        if sorted {
+            batch.sort();
+            s.insert_sorted_batch(batch.iter().cloned());
+            multiset.insert_sorted_batch(batch.iter().map(|v| v.key));
+        } else {
+            s.extend(batch.iter().cloned());
+            multiset.extend(batch.iter().map(|v| &v.key));
+        }
+        for v in batch {
+            model.insert(v.clone());
+            multiset_model.push(v.key);
+        }
+        multiset_model.sort();
+        prop_assert!(s.iter().map(|v| (v.key, v.tag)).eq(model.iter().map(|v| (v.key, v.tag))));
+        prop_assert!(multiset.iter().eq(multiset_model.iter()));
+    }
+
+    #[test]
+    fn cursor_walk(values: Vec<u8>, bound: u8) {
+        // build by insertion, so that subarrays are rotated
+        let mut s = RotatedArraySet::new();
+        for &v in &values {
+            s.insert(v);
+        }
+        let model: BTreeSet<_> = values.into_iter().collect();
+        let mut cursor = s.lower_bound(Bound::Excluded(&bound));
+        for v in model.range((Bound::Excluded(bound), Bound::Unbounded)) {
+            prop_assert_eq!(cursor.current(), Some(v));
+            cursor.move_next();
+        }
+        prop_assert_eq!(cursor.current(), None);
+        prop_assert_eq!(cursor.peek_next(), model.iter().next());
+        let mut cursor = s.upper_bound(Bound::Included(&bound));
+        for v in model.range(..=bound).rev() {
+            prop_assert_eq!(cursor.current(), Some(v));
+            prop_assert_eq!(s.select(cursor.index().unwrap()), Some(v));
+            cursor.move_prev();
+        }
+        prop_assert_eq!(cursor.index(), None);
+        prop_assert_eq!(cursor.peek_prev(), model.iter().next_back());
+    }
+
+    #[test]
+    fn cursor_edit(values: Vec<u8>, ops in prop::collection::vec((0u8..5, any::<u8>()), 0..256)) {
+        // build by insertion, so that subarrays are rotated
+        let mut s = RotatedArraySet::new();
+        for &v in &values {
+            s.insert(v);
+        }
+        let mut model: Vec<_> = s.iter().cloned().collect();
+        // the model cursor position, where `model.len()` is the ghost
+        let mut pos = 0;
+        let mut cursor = s.cursor_at_mut(0);
+        for (op, v) in ops {
+            let len = model.len();
+            match op {
+                0 => {
+                    cursor.move_next();
+                    pos = (pos + 1) % (len + 1);
+                }
+                1 => {
+                    cursor.move_prev();
+                    pos = (pos + len) % (len + 1);
+                }
+                2 => {
+                    let expected = if pos < len { Some(model.remove(pos)) } else { None };
+                    prop_assert_eq!(cursor.remove_current(), expected);
+                }
+                3 => {
+                    let fits = (pos == 0 || model[pos - 1] < v) && (pos == len || v < model[pos]);
+                    if fits {
+                        model.insert(pos, v);
+                        pos += 1;
+                        prop_assert_eq!(cursor.insert_before(v), Ok(()));
+                    } else {
+                        prop_assert_eq!(cursor.insert_before(v), Err(v));
+                    }
+                }
+                _ => {
+                    let after = if pos == len { 0 } else { pos + 1 };
+                    let fits = (after == 0 || model[after - 1] < v) && (after == len || v < model[after]);
+                    if fits {
+                        model.insert(after, v);
+                        if pos == len {
+                            pos += 1;
+                        }
+                        prop_assert_eq!(cursor.insert_after(v), Ok(()));
+                    } else {
+                        prop_assert_eq!(cursor.insert_after(v), Err(v));
+                    }
+                }
+            }
+            prop_assert_eq!(cursor.current(), model.get(pos));
+            prop_assert_eq!(cursor.index(), if pos < model.len() { Some(pos) } else { None });
+        }
+        prop_assert!(s.iter().eq(model.iter()));
+    }
+
+    #[test]
+    fn chunks_iter(values: Vec<u16>, ops in prop::collection::vec((0u8..4, 0usize..8), 0..64)) {
+        let mut s = RotatedArraySet::new();
+        for v in &values {
+            s.insert(*v);
+        }
+        let model: BTreeSet<u16> = values.iter().cloned().collect();
+        for chunk in s.chunks() {
+            prop_assert!(!chunk.is_empty());
+            prop_assert!(chunk.windows(2).all(|w| w[0] < w[1]));
+        }
+        prop_assert!(s.chunks().flatten().eq(model.iter()));
+        prop_assert!(s.chunks().rev().flat_map(|c| c.iter().rev()).eq(model.iter().rev()));
+        let mut iter = s.iter();
+        let mut expected = model.iter();
+        for (op, n) in ops {
+            match op {
+                0 => prop_assert_eq!(iter.next(), expected.next()),
+                1 => prop_assert_eq!(iter.next_back(), expected.next_back()),
+                2 => prop_assert_eq!(iter.nth(n), expected.nth(n)),
+                _ => prop_assert_eq!(iter.nth_back(n), expected.nth_back(n)),
+            }
+            prop_assert_eq!(iter.len(), expected.len());
+        }
+        prop_assert!(iter.eq(expected));
+    }
 }